@@ -0,0 +1,219 @@
+//! Per-zone Range Calibration.
+//!
+//! All distance values are considered to be in millimeter.
+
+use crate::math::CoordsCartesian;
+use crate::measurements::{self, SensorParams};
+use crate::SensorMeasurement;
+
+/// Per-zone range calibration, correcting range offsets and the crosstalk of a cover glass.
+///
+/// The crosstalk is modeled as a bias that is proportional to the measured distance,
+/// so the corrected distance of a zone is `dist + offset + crosstalk * dist`.
+/// The zones are indexed after the mounting orientation was applied.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoneCalibration<const RES_X: usize, const RES_Y: usize> {
+    /// The range offset of each zone.
+    pub offset: [[f32; RES_X]; RES_Y],
+    /// The crosstalk coefficient of each zone.
+    pub crosstalk: [[f32; RES_X]; RES_Y],
+}
+
+impl<const RES_X: usize, const RES_Y: usize> ZoneCalibration<RES_X, RES_Y> {
+    /// The identity calibration, that does not change the distances.
+    pub fn identity() -> Self {
+        Self {
+            offset: [[0.0; RES_X]; RES_Y],
+            crosstalk: [[0.0; RES_X]; RES_Y],
+        }
+    }
+
+    /// Applies the calibration to the valid distances of the measurement.
+    pub fn apply(
+        &self,
+        mut measurement: SensorMeasurement<RES_X, RES_Y>,
+    ) -> SensorMeasurement<RES_X, RES_Y> {
+        for (pos_y, row) in measurement.zone_dist.iter_mut().enumerate() {
+            for (pos_x, dist) in row.iter_mut().enumerate() {
+                if *dist > 0.0 {
+                    let corrected =
+                        *dist + self.offset[pos_y][pos_x] + self.crosstalk[pos_y][pos_x] * *dist;
+                    // Stays valid, even if the correction is larger than the distance
+                    *dist = corrected.max(f32::MIN_POSITIVE);
+                }
+            }
+        }
+
+        measurement
+    }
+}
+
+/// Computes a [ZoneCalibration] from frames of a flat target, that faces the sensor at a known distance.
+///
+/// With frames at a single target distance only the offsets can be computed.
+/// With frames at multiple target distances the crosstalk is computed as well, through a least squares fit of the error.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZoneCalibrator<const RES_X: usize, const RES_Y: usize> {
+    n: [[u32; RES_X]; RES_Y],
+    sum_dist: [[f32; RES_X]; RES_Y],
+    sum_dist_sq: [[f32; RES_X]; RES_Y],
+    sum_err: [[f32; RES_X]; RES_Y],
+    sum_dist_err: [[f32; RES_X]; RES_Y],
+}
+
+impl<const RES_X: usize, const RES_Y: usize> ZoneCalibrator<RES_X, RES_Y> {
+    /// The minimum spread of the measured distances (standard deviation) to compute the crosstalk.
+    const MIN_DIST_SPREAD: f32 = 10.0;
+
+    /// A new calibrator without any frames.
+    pub fn new() -> Self {
+        Self {
+            n: [[0; RES_X]; RES_Y],
+            sum_dist: [[0.0; RES_X]; RES_Y],
+            sum_dist_sq: [[0.0; RES_X]; RES_Y],
+            sum_err: [[0.0; RES_X]; RES_Y],
+            sum_dist_err: [[0.0; RES_X]; RES_Y],
+        }
+    }
+
+    /// Adds a frame of a flat target, that is perpendicular to the sensor axis at the given distance.
+    ///
+    /// The expected distance of every zone is derived from its direction in the sensor FOV.
+    /// The zones are oriented like in the recognizer, frames are ignored if the orientation is not supported.
+    pub fn add_frame(
+        &mut self,
+        measurement: &SensorMeasurement<RES_X, RES_Y>,
+        target_dist: f32,
+        params: &SensorParams,
+    ) {
+        let Some(measurement) = measurement.oriented(params) else {
+            return;
+        };
+
+        for (pos_y, row) in measurement.zone_dist.iter().enumerate() {
+            for (pos_x, &dist) in row.iter().enumerate() {
+                if dist <= 0.0 {
+                    continue;
+                }
+                let direction = CoordsCartesian::from(measurements::dist_position_spher::<
+                    RES_X,
+                    RES_Y,
+                >(1.0, pos_x, pos_y, params));
+                if direction.x <= 0.0 {
+                    continue;
+                }
+                let err = target_dist / direction.x - dist;
+
+                self.n[pos_y][pos_x] += 1;
+                self.sum_dist[pos_y][pos_x] += dist;
+                self.sum_dist_sq[pos_y][pos_x] += dist * dist;
+                self.sum_err[pos_y][pos_x] += err;
+                self.sum_dist_err[pos_y][pos_x] += dist * err;
+            }
+        }
+    }
+
+    /// Computes the calibration from the added frames.
+    ///
+    /// Zones without any valid distance in the frames are not corrected.
+    pub fn calibration(&self) -> ZoneCalibration<RES_X, RES_Y> {
+        let mut calibration = ZoneCalibration::identity();
+
+        for pos_y in 0..RES_Y {
+            for pos_x in 0..RES_X {
+                if self.n[pos_y][pos_x] == 0 {
+                    continue;
+                }
+                let n = self.n[pos_y][pos_x] as f32;
+                let mean_dist = self.sum_dist[pos_y][pos_x] / n;
+                let mean_err = self.sum_err[pos_y][pos_x] / n;
+                let var_dist = self.sum_dist_sq[pos_y][pos_x] / n - mean_dist * mean_dist;
+
+                let crosstalk = if var_dist >= Self::MIN_DIST_SPREAD * Self::MIN_DIST_SPREAD {
+                    (self.sum_dist_err[pos_y][pos_x] / n - mean_dist * mean_err) / var_dist
+                } else {
+                    0.0
+                };
+
+                calibration.crosstalk[pos_y][pos_x] = crosstalk;
+                calibration.offset[pos_y][pos_x] = mean_err - crosstalk * mean_dist;
+            }
+        }
+
+        calibration
+    }
+}
+
+impl<const RES_X: usize, const RES_Y: usize> Default for ZoneCalibrator<RES_X, RES_Y> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZoneCalibrator;
+    use crate::math::CoordsCartesian;
+    use crate::measurements::{self, SensorParams};
+    use crate::SensorMeasurement;
+    use approx::assert_relative_eq;
+
+    /// Simulates the measurement of a flat target, reading 30mm short and with 2% crosstalk bias.
+    fn measure_flat_target(target_dist: f32, params: &SensorParams) -> SensorMeasurement<4, 4> {
+        let mut measurement = SensorMeasurement::<4, 4>::invalid();
+
+        for (pos_y, row) in measurement.zone_dist.iter_mut().enumerate() {
+            for (pos_x, dist) in row.iter_mut().enumerate() {
+                let direction = CoordsCartesian::from(measurements::dist_position_spher::<4, 4>(
+                    1.0, pos_x, pos_y, params,
+                ));
+                let expected = target_dist / direction.x;
+                *dist = (expected - 30.0) / 1.02;
+            }
+        }
+
+        measurement
+    }
+
+    #[test]
+    fn calibrator_offset_and_crosstalk() {
+        let params = SensorParams::default_vl53l5cx();
+        let mut calibrator = ZoneCalibrator::<4, 4>::new();
+
+        calibrator.add_frame(&measure_flat_target(200.0, &params), 200.0, &params);
+        calibrator.add_frame(&measure_flat_target(400.0, &params), 400.0, &params);
+
+        let calibration = calibrator.calibration();
+        let corrected = calibration.apply(measure_flat_target(300.0, &params));
+        let expected = measure_flat_target(300.0, &params)
+            .zone_dist
+            .map(|row| row.map(|d| d * 1.02 + 30.0));
+
+        for (row, expected_row) in corrected.zone_dist.iter().zip(expected) {
+            for (d, expected) in row.iter().zip(expected_row) {
+                assert_relative_eq!(*d, expected, epsilon = 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn calibrator_offset_only() {
+        let params = SensorParams::default_vl53l5cx();
+        let mut calibrator = ZoneCalibrator::<4, 4>::new();
+
+        calibrator.add_frame(&measure_flat_target(300.0, &params), 300.0, &params);
+
+        let calibration = calibrator.calibration();
+        assert_relative_eq!(calibration.crosstalk[1][1], 0.0);
+
+        let measured = measure_flat_target(300.0, &params);
+        let corrected = calibration.apply(measured);
+        assert_relative_eq!(
+            corrected.zone_dist[1][1],
+            measured.zone_dist[1][1] * 1.02 + 30.0,
+            epsilon = 0.1
+        );
+    }
+}
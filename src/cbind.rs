@@ -1,9 +1,13 @@
 //! C Bindings.
 
+use crate::calibration::{ZoneCalibration, ZoneCalibrator};
 use crate::math::{CoordsCartesian, CoordsSpherical};
-use crate::measurements::SensorParams;
+use crate::measurements::{MultiTargetMeasurement, SensorParams};
+use crate::preprocessing::BackgroundModel;
+use crate::presets::{SensorPreset, SensorPresetId};
 use crate::{
-    GestureRecognizer, RecognizerParams, RecognizerResult, RecognizerStatus, SensorMeasurement,
+    FusionGestureRecognizer, GestureRecognizer, HandTracker, RecognizerParams, RecognizerResult,
+    RecognizerStatus, RuntimeResGestureRecognizer, SensorMeasurement, ZoneQuality,
 };
 
 /// The horizontal sensor resolution.
@@ -24,21 +28,92 @@ pub const RES_Y: usize = 8;
 
 /// The history size.
 ///
-/// Must be large enough to hold at least ca 2 seconds of data to be able to reliably recognize gestures.
+/// Must be large enough to hold at least ca 2 seconds of data to be able to reliably recognize gestures,
+/// and at least the `min_history_size` of the used sensor preset, e.g. 91 for the 4x4 presets at 60 Hz.
 pub const HISTORY_SIZE: usize = 60;
 
+/// The number of targets per zone of multi-target measurements.
+///
+/// For users of the C bindings:
+/// Change the value of this definition to match the configured number of targets per zone of the sensor.
+///
+/// For VL53L5CX: Up to 4 (`VL53L5CX_NB_TARGET_PER_ZONE`).
+pub const TARGETS_PER_ZONE: usize = 2;
+
+/// The number of sensors of the fusion gesture recognizer.
+///
+/// For users of the C bindings:
+/// Change the value of this definition to match the number of fused sensors.
+pub const FUSION_SENSORS: usize = 2;
+
 /// Creates an invalid measurement (distances are set to `-1.0`).
 #[no_mangle]
 pub extern "C" fn sensor_measurement_invalid() -> SensorMeasurement<RES_X, RES_Y> {
     SensorMeasurement::<RES_X, RES_Y>::invalid()
 }
 
+/// Creates an invalid multi-target measurement (all targets are set to invalid).
+#[no_mangle]
+pub extern "C" fn multi_target_measurement_invalid(
+) -> MultiTargetMeasurement<RES_X, RES_Y, TARGETS_PER_ZONE> {
+    MultiTargetMeasurement::invalid()
+}
+
 /// Default sensor parameters for the ST VL53L5CX TOF-Sensor.
 #[no_mangle]
 pub extern "C" fn sensor_params_default_vl53l5cx() -> SensorParams {
     SensorParams::default_vl53l5cx()
 }
 
+/// Default sensor parameters for the ST VL53L7CX TOF-Sensor.
+#[no_mangle]
+pub extern "C" fn sensor_params_default_vl53l7cx() -> SensorParams {
+    SensorParams::default_vl53l7cx()
+}
+
+/// Default sensor parameters for the ST VL53L8CX TOF-Sensor.
+#[no_mangle]
+pub extern "C" fn sensor_params_default_vl53l8cx() -> SensorParams {
+    SensorParams::default_vl53l8cx()
+}
+
+/// Default sensor parameters for the ams TMF8820, TMF8821 and TMF8828 TOF-Sensors.
+#[no_mangle]
+pub extern "C" fn sensor_params_default_tmf882x() -> SensorParams {
+    SensorParams::default_tmf882x()
+}
+
+/// Gets the sensor preset with the given id, containing the sensor parameters and the recommended recognizer parameters.
+///
+/// The resolution of the preset must match `RES_X` and `RES_Y`.
+#[no_mangle]
+pub extern "C" fn sensor_preset_get(id: SensorPresetId) -> SensorPreset {
+    SensorPreset::get(id)
+}
+
+/// Masks the zone at the given position in the sensor parameters, so that it is ignored.
+///
+/// Positions outside of `ZONE_MASK_MAX_RES` are ignored.
+#[no_mangle]
+pub extern "C" fn sensor_params_mask_zone(
+    sensor_params: &mut SensorParams,
+    pos_x: usize,
+    pos_y: usize,
+) {
+    sensor_params.mask_zone(pos_x, pos_y)
+}
+
+/// Sets the pose of the sensor relative to the user from euler angles (deg).
+#[no_mangle]
+pub extern "C" fn sensor_params_set_pose_euler(
+    sensor_params: &mut SensorParams,
+    yaw: f32,
+    pitch: f32,
+    roll: f32,
+) {
+    sensor_params.set_pose_euler(yaw, pitch, roll)
+}
+
 /// Default recognizer parameters, providing a good starting point for gesture recognition.
 #[no_mangle]
 pub extern "C" fn recognizer_params_default() -> RecognizerParams {
@@ -53,20 +128,39 @@ pub extern "C" fn recognizer_result_default() -> RecognizerResult {
     RecognizerResult::default()
 }
 
-/// A new gesture recognizer with the given parameters.
+/// Initializes a new gesture recognizer with the given parameters.
 ///
 /// The sensor parameters have preconfigured defaults for common TOF-Sensors such as the ST VL53L5CX.
+/// Rotations by 90 or 270 degrees of the mounting orientation require `RES_X` and `RES_Y` to be equal.
+/// Returns `RecognizerStatusInitFailure` and leaves the gesture recognizer untouched if the mounting orientation
+/// is not supported, `RecognizerStatusInvalidInput` if `gesture_recognizer` is `NULL`.
+///
+/// # Safety
+///
+/// `gesture_recognizer` must be `NULL` or point to memory valid for writing a gesture recognizer.
 #[no_mangle]
-pub extern "C" fn gesture_recognizer_new(
+pub unsafe extern "C" fn gesture_recognizer_init(
+    gesture_recognizer: *mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
     params: RecognizerParams,
     sensor_params: SensorParams,
-) -> GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE> {
-    GestureRecognizer::new(params, sensor_params)
+) -> RecognizerStatus {
+    if gesture_recognizer.is_null() {
+        return RecognizerStatus::RecognizerStatusInvalidInput;
+    }
+    match GestureRecognizer::new(params, sensor_params) {
+        Ok(recognizer) => {
+            gesture_recognizer.write(recognizer);
+            RecognizerStatus::RecognizerStatusOk
+        }
+        Err(error) => error.into(),
+    }
 }
 
 /// Resets the gesture recognizer with the given parameters.
 ///
-/// Clears the history, ongoing predictions and resets the internal state.
+/// Clears the history, ongoing predictions and resets the internal state,
+/// including the adaptive threshold, the zone filter and the presence detection.
+/// Returns `RecognizerStatusInitFailure` and keeps the current parameters if the mounting orientation is not supported.
 #[no_mangle]
 pub extern "C" fn gesture_recognizer_reset(
     gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
@@ -89,6 +183,338 @@ pub extern "C" fn gesture_recognizer_update(
     gesture_recognizer.update(measurement, gesture_result)
 }
 
+/// Updates the gesture recognizer with a new measurement and the quality of its zones.
+///
+/// Zones that are not accepted by the quality thresholds of the sensor parameters are ignored,
+/// the others are weighted with their confidence.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_update_with_quality(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    measurement: SensorMeasurement<RES_X, RES_Y>,
+    zone_quality: &[[ZoneQuality; RES_X]; RES_Y],
+    gesture_result: &mut RecognizerResult,
+) -> RecognizerStatus {
+    gesture_recognizer.update_with_quality(measurement, zone_quality, gesture_result)
+}
+
+/// Initializes a new gesture recognizer with a resolution that is selected at runtime, up to `RES_X` and `RES_Y`.
+///
+/// Initially at the maximum resolution.
+/// Returns the same status as `gesture_recognizer_init`.
+///
+/// # Safety
+///
+/// `gesture_recognizer` must be `NULL` or point to memory valid for writing a gesture recognizer.
+#[no_mangle]
+pub unsafe extern "C" fn runtime_res_gesture_recognizer_init(
+    gesture_recognizer: *mut RuntimeResGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    params: RecognizerParams,
+    sensor_params: SensorParams,
+) -> RecognizerStatus {
+    if gesture_recognizer.is_null() {
+        return RecognizerStatus::RecognizerStatusInvalidInput;
+    }
+    match RuntimeResGestureRecognizer::new(params, sensor_params) {
+        Ok(recognizer) => {
+            gesture_recognizer.write(recognizer);
+            RecognizerStatus::RecognizerStatusOk
+        }
+        Err(error) => error.into(),
+    }
+}
+
+/// Resets the runtime resolution gesture recognizer with the given parameters.
+#[no_mangle]
+pub extern "C" fn runtime_res_gesture_recognizer_reset(
+    gesture_recognizer: &mut RuntimeResGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    params: RecognizerParams,
+    sensor_params: SensorParams,
+    now: u32,
+) -> RecognizerStatus {
+    gesture_recognizer.reset(params, sensor_params, now)
+}
+
+/// Changes the resolution of the measurements, resetting the recognizer and clearing the background.
+///
+/// Returns `RecognizerStatusInvalidInput` if the resolution is zero or exceeds `RES_X` or `RES_Y`.
+#[no_mangle]
+pub extern "C" fn runtime_res_gesture_recognizer_set_resolution(
+    gesture_recognizer: &mut RuntimeResGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    res_x: usize,
+    res_y: usize,
+    now: u32,
+) -> RecognizerStatus {
+    gesture_recognizer.set_resolution(res_x, res_y, now)
+}
+
+/// Updates the runtime resolution gesture recognizer with a new measurement at the current resolution.
+///
+/// `zone_dist` and the optional `zone_quality` (may be `NULL`) contain `n_zones` zones in row-major order.
+/// Returns `RecognizerStatusInvalidInput` if a required pointer is `NULL`
+/// or if `n_zones` does not match the current resolution.
+///
+/// # Safety
+///
+/// `gesture_recognizer` and `gesture_result` must be `NULL` or valid,
+/// `zone_dist` must point to `n_zones` values, `zone_quality` must be `NULL` or point to `n_zones` values.
+#[no_mangle]
+pub unsafe extern "C" fn runtime_res_gesture_recognizer_update(
+    gesture_recognizer: *mut RuntimeResGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    zone_dist: *const f32,
+    zone_quality: *const ZoneQuality,
+    n_zones: usize,
+    time_ms: u32,
+    gesture_result: *mut RecognizerResult,
+) -> RecognizerStatus {
+    let (Some(gesture_recognizer), Some(gesture_result)) =
+        (gesture_recognizer.as_mut(), gesture_result.as_mut())
+    else {
+        return RecognizerStatus::RecognizerStatusInvalidInput;
+    };
+    // More zones than the maximum resolution never match, so the slices are not created from them
+    if zone_dist.is_null() || n_zones > RES_X * RES_Y {
+        *gesture_result = RecognizerResult::default();
+        return RecognizerStatus::RecognizerStatusInvalidInput;
+    }
+    let zone_dist = core::slice::from_raw_parts(zone_dist, n_zones);
+    let zone_quality = if zone_quality.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(zone_quality, n_zones)
+    };
+
+    gesture_recognizer.update(zone_dist, zone_quality, time_ms, gesture_result)
+}
+
+/// Updates the gesture recognizer with a new measurement with multiple targets per zone.
+///
+/// The nearest valid target of every zone is used for recognition.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_update_multi_target(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    measurement: MultiTargetMeasurement<RES_X, RES_Y, TARGETS_PER_ZONE>,
+    gesture_result: &mut RecognizerResult,
+) -> RecognizerStatus {
+    gesture_recognizer.update_multi_target(measurement, gesture_result)
+}
+
+/// Whether something is currently present in presence mode.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_is_present(
+    gesture_recognizer: &GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) -> bool {
+    gesture_recognizer.is_present()
+}
+
+/// Gets the current furthest hand distance for gesture recognition.
+///
+/// Either the configured fixed distance or the current adaptive distance, depending on the threshold mode.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_gesture_threshold_dist(
+    gesture_recognizer: &GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) -> f32 {
+    gesture_recognizer.gesture_threshold_dist()
+}
+
+/// Gets the per-zone range calibration of the gesture recognizer.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_calibration(
+    gesture_recognizer: &GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) -> ZoneCalibration<RES_X, RES_Y> {
+    gesture_recognizer.calibration()
+}
+
+/// Sets the per-zone range calibration, that is applied to every measurement before the hand is recognized.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_set_calibration(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    calibration: ZoneCalibration<RES_X, RES_Y>,
+) {
+    gesture_recognizer.set_calibration(calibration)
+}
+
+/// A new zone calibrator without any frames.
+#[no_mangle]
+pub extern "C" fn zone_calibrator_new() -> ZoneCalibrator<RES_X, RES_Y> {
+    ZoneCalibrator::new()
+}
+
+/// Adds a frame of a flat target, that is perpendicular to the sensor axis at the given distance.
+#[no_mangle]
+pub extern "C" fn zone_calibrator_add_frame(
+    calibrator: &mut ZoneCalibrator<RES_X, RES_Y>,
+    measurement: SensorMeasurement<RES_X, RES_Y>,
+    target_dist: f32,
+    sensor_params: SensorParams,
+) {
+    calibrator.add_frame(&measurement, target_dist, &sensor_params)
+}
+
+/// Computes the zone calibration from the added frames.
+#[no_mangle]
+pub extern "C" fn zone_calibrator_calibration(
+    calibrator: &ZoneCalibrator<RES_X, RES_Y>,
+) -> ZoneCalibration<RES_X, RES_Y> {
+    calibrator.calibration()
+}
+
+/// Learns the static background from a measurement with no hand present.
+///
+/// Should be called with several frames. Afterwards only zones that are significantly closer
+/// than the background count towards hand recognition.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_calibrate_background(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    measurement: SensorMeasurement<RES_X, RES_Y>,
+) -> RecognizerStatus {
+    gesture_recognizer.calibrate_background(measurement)
+}
+
+/// Gets the calibrated background, e.g. to store it.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_background(
+    gesture_recognizer: &GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) -> BackgroundModel<RES_X, RES_Y> {
+    gesture_recognizer.background()
+}
+
+/// Sets the background, e.g. a previously stored one.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_set_background(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+    background: BackgroundModel<RES_X, RES_Y>,
+) {
+    gesture_recognizer.set_background(background)
+}
+
+/// Clears the background, all zones then count towards hand recognition again.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_clear_background(
+    gesture_recognizer: &mut GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) {
+    gesture_recognizer.clear_background()
+}
+
+/// Initializes a new fusion gesture recognizer with the given parameters and the sensor parameters of every sensor.
+///
+/// Every sensor is placed in a shared frame through the pose of its sensor parameters.
+/// Returns `RecognizerStatusInitFailure` and leaves the gesture recognizer untouched if the presence mode is enabled
+/// or if the mounting orientation of a sensor is not supported, `RecognizerStatusInvalidInput` if `gesture_recognizer`
+/// is `NULL`.
+///
+/// # Safety
+///
+/// `gesture_recognizer` must be `NULL` or point to memory valid for writing a fusion gesture recognizer.
+#[no_mangle]
+pub unsafe extern "C" fn fusion_gesture_recognizer_init(
+    gesture_recognizer: *mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    params: RecognizerParams,
+    sensor_params: &[SensorParams; FUSION_SENSORS],
+) -> RecognizerStatus {
+    if gesture_recognizer.is_null() {
+        return RecognizerStatus::RecognizerStatusInvalidInput;
+    }
+    match FusionGestureRecognizer::new(params, *sensor_params) {
+        Ok(recognizer) => {
+            gesture_recognizer.write(recognizer);
+            RecognizerStatus::RecognizerStatusOk
+        }
+        Err(error) => error.into(),
+    }
+}
+
+/// Resets the fusion gesture recognizer with the given parameters.
+///
+/// Clears the history, ongoing predictions and resets the internal state,
+/// including the adaptive thresholds and the zone filters.
+///
+/// Returns `RecognizerStatusInitFailure` and keeps the current parameters if the presence mode is enabled
+/// or if the mounting orientation of a sensor is not supported.
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_reset(
+    gesture_recognizer: &mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    params: RecognizerParams,
+    sensor_params: &[SensorParams; FUSION_SENSORS],
+    now: u32,
+) -> RecognizerStatus {
+    gesture_recognizer.reset(params, *sensor_params, now)
+}
+
+/// Updates the fusion gesture recognizer with new measurements of every sensor.
+///
+/// The time must be monotonically increasing (usually coming from a systick timer).
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_update(
+    gesture_recognizer: &mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    measurements: &[SensorMeasurement<RES_X, RES_Y>; FUSION_SENSORS],
+    gesture_result: &mut RecognizerResult,
+) -> RecognizerStatus {
+    gesture_recognizer.update(*measurements, gesture_result)
+}
+
+/// Sets the per-zone range calibration of a sensor of the fusion gesture recognizer.
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_set_calibration(
+    gesture_recognizer: &mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    sensor: usize,
+    calibration: ZoneCalibration<RES_X, RES_Y>,
+) -> RecognizerStatus {
+    gesture_recognizer.set_calibration(sensor, calibration)
+}
+
+/// Learns the static background of a sensor of the fusion gesture recognizer from a measurement with no hand present.
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_calibrate_background(
+    gesture_recognizer: &mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    sensor: usize,
+    measurement: SensorMeasurement<RES_X, RES_Y>,
+) -> RecognizerStatus {
+    gesture_recognizer.calibrate_background(sensor, measurement)
+}
+
+/// Clears the background of a sensor of the fusion gesture recognizer.
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_clear_background(
+    gesture_recognizer: &mut FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+    sensor: usize,
+) -> RecognizerStatus {
+    gesture_recognizer.clear_background(sensor)
+}
+
+/// Gets the hand tracker of the fusion gesture recognizer, providing the estimated hand position and velocity.
+#[no_mangle]
+pub extern "C" fn fusion_gesture_recognizer_tracker(
+    gesture_recognizer: &FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, FUSION_SENSORS>,
+) -> &HandTracker {
+    gesture_recognizer.tracker()
+}
+
+/// Gets the hand tracker of the gesture recognizer, providing the estimated hand position and velocity.
+#[no_mangle]
+pub extern "C" fn gesture_recognizer_tracker(
+    gesture_recognizer: &GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>,
+) -> &HandTracker {
+    gesture_recognizer.tracker()
+}
+
+/// The estimated hand position of the tracker (mm).
+#[no_mangle]
+pub extern "C" fn hand_tracker_position(tracker: &HandTracker) -> CoordsCartesian {
+    tracker.position()
+}
+
+/// The estimated hand velocity of the tracker (mm/s).
+#[no_mangle]
+pub extern "C" fn hand_tracker_velocity(tracker: &HandTracker) -> CoordsCartesian {
+    tracker.velocity()
+}
+
+/// Whether the hand is currently tracked.
+#[no_mangle]
+pub extern "C" fn hand_tracker_is_tracking(tracker: &HandTracker) -> bool {
+    tracker.is_tracking()
+}
+
 /// Converts cartesian to spherical coordinates.
 #[no_mangle]
 pub extern "C" fn coords_spherical_from_cartesian(coords_cart: CoordsCartesian) -> CoordsSpherical {
@@ -102,3 +528,75 @@ pub extern "C" fn coords_cartesian_from_spherical(
 ) -> CoordsCartesian {
     coords_spher.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{runtime_res_gesture_recognizer_init, runtime_res_gesture_recognizer_update};
+    use crate::measurements::SensorParams;
+    use crate::{RecognizerParams, RecognizerResult, RecognizerStatus};
+    use core::mem::MaybeUninit;
+    use core::ptr;
+
+    #[test]
+    fn runtime_res_init_null() {
+        assert!(matches!(
+            unsafe {
+                runtime_res_gesture_recognizer_init(
+                    ptr::null_mut(),
+                    RecognizerParams::default(),
+                    SensorParams::default_vl53l5cx(),
+                )
+            },
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+    }
+
+    #[test]
+    fn runtime_res_update_null() {
+        let mut recognizer = MaybeUninit::uninit();
+        assert!(matches!(
+            unsafe {
+                runtime_res_gesture_recognizer_init(
+                    recognizer.as_mut_ptr(),
+                    RecognizerParams::default(),
+                    SensorParams::default_vl53l5cx(),
+                )
+            },
+            RecognizerStatus::RecognizerStatusOk
+        ));
+        let mut recognizer = unsafe { recognizer.assume_init() };
+        let mut result = RecognizerResult::default();
+        let zone_dist = [300.0; 64];
+
+        let update = |recognizer, zone_dist, n_zones, result| unsafe {
+            runtime_res_gesture_recognizer_update(
+                recognizer,
+                zone_dist,
+                ptr::null(),
+                n_zones,
+                10,
+                result,
+            )
+        };
+        assert!(matches!(
+            update(ptr::null_mut(), zone_dist.as_ptr(), 64, &mut result),
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+        assert!(matches!(
+            update(&mut recognizer, zone_dist.as_ptr(), 64, ptr::null_mut()),
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+        assert!(matches!(
+            update(&mut recognizer, ptr::null(), 64, &mut result),
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+        assert!(matches!(
+            update(&mut recognizer, zone_dist.as_ptr(), usize::MAX, &mut result),
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+        assert!(matches!(
+            update(&mut recognizer, zone_dist.as_ptr(), 64, &mut result),
+            RecognizerStatus::RecognizerStatusOk
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+//! Fixed-point Arithmetic.
+//!
+//! Used for the per-zone computations of the hand recognition with the `fixed-point` feature,
+//! see [crate::math::Scalar]. All other computations stay `f32`.
+
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// The number of fractional bits of [Fixed].
+pub const FIXED_FRAC_BITS: u32 = 12;
+
+/// A signed fixed-point number with 12 fractional bits, stored in a 32-bit integer (Q19.12).
+///
+/// Distances in millimeter are covered up to about ±524 m with a resolution of 1/4096 mm.
+/// Multiplications and divisions use 64-bit intermediates and round to the nearest value.
+/// All arithmetic operations saturate at the bounds, a division by zero saturates towards the sign of the dividend.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Fixed(i32);
+
+impl Fixed {
+    /// Zero.
+    pub const ZERO: Self = Self(0);
+    /// One.
+    pub const ONE: Self = Self(1 << FIXED_FRAC_BITS);
+    /// π/2.
+    pub const FRAC_PI_2: Self = Self(6434);
+    /// π.
+    pub const PI: Self = Self(12868);
+    /// 2π.
+    pub const TAU: Self = Self(25736);
+
+    /// Creates a fixed-point number from its raw bits.
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// The raw bits of the fixed-point number.
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    /// Converts an integer, which must be in range of the fixed-point number.
+    pub const fn from_int(value: i32) -> Self {
+        Self(value << FIXED_FRAC_BITS)
+    }
+
+    /// Converts a float, saturating at the bounds of the fixed-point number. NaN is converted to zero.
+    ///
+    /// Only integer operations on the bits of the float are used, so no floating point operations are emulated.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let fraction = (bits & 0x7f_ffff) as i64;
+        if exponent == 0xff && fraction != 0 {
+            return Self::ZERO;
+        }
+        // The float is mantissa * 2^(exponent - 150), with the implicit leading bit for normal numbers
+        let mantissa = if exponent == 0 {
+            fraction
+        } else {
+            fraction | 0x80_0000
+        };
+        let shift = exponent - 150 + FIXED_FRAC_BITS as i32;
+
+        let magnitude = if shift > 39 {
+            i64::MAX
+        } else if shift >= 0 {
+            mantissa << shift
+        } else if shift > -25 {
+            // Round half away from zero
+            (mantissa + (1 << (-shift - 1))) >> -shift
+        } else {
+            0
+        };
+        let magnitude = magnitude.min(i32::MAX as i64) as i32;
+
+        Self(if bits >> 31 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        })
+    }
+
+    /// Converts to a float.
+    pub fn to_f32(self) -> f32 {
+        // Exact, as the scale is a power of two
+        self.0 as f32 * (1.0 / Self::ONE.0 as f32)
+    }
+
+    /// The absolute value.
+    pub fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    /// Converts a 64-bit intermediate, saturating at the bounds.
+    fn saturated(value: i64) -> Self {
+        Self(value.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    /// The square root, zero for negative numbers.
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+
+        Self(((self.0 as u64) << FIXED_FRAC_BITS).isqrt() as i32)
+    }
+
+    /// The length of the vector (self, other), without overflowing for large components.
+    pub fn hypot(self, other: Self) -> Self {
+        let squares = (self.0 as i64).pow(2) as u64 + (other.0 as i64).pow(2) as u64;
+
+        Self::saturated(squares.isqrt() as i64)
+    }
+
+    /// The sine of an angle (rad).
+    pub fn sin(self) -> Self {
+        // Reduce to [-π, π], then to [-π/2, π/2] using the symmetry around ±π/2
+        let mut x = Self(self.0 % Self::TAU.0);
+        if x > Self::PI {
+            x -= Self::TAU;
+        } else if x < -Self::PI {
+            x += Self::TAU;
+        }
+        if x > Self::FRAC_PI_2 {
+            x = Self::PI - x;
+        } else if x < -Self::FRAC_PI_2 {
+            x = -Self::PI - x;
+        }
+
+        // Taylor series up to x^9, in horner form
+        let x2 = x * x;
+        let term = |divisor: i32, inner: Self| Self::ONE - x2 * inner / Self::from_int(divisor);
+        x * term(6, term(20, term(42, term(72, Self::ONE))))
+    }
+
+    /// The cosine of an angle (rad).
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+
+    /// The tangent of an angle (rad), zero where it is undefined.
+    pub fn tan(self) -> Self {
+        let cos = self.cos();
+
+        if cos == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        self.sin() / cos
+    }
+
+    /// The arctangent (rad).
+    pub fn atan(self) -> Self {
+        self.atan2(Self::ONE)
+    }
+
+    /// The four quadrant arctangent of self (y) and other (x) (rad), zero if both are zero.
+    pub fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self, other);
+
+        if x == Self::ZERO && y == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        if y.abs() <= x.abs() {
+            let angle = atan_unit(y / x);
+            if x > Self::ZERO {
+                angle
+            } else if y >= Self::ZERO {
+                angle + Self::PI
+            } else {
+                angle - Self::PI
+            }
+        } else {
+            let angle = atan_unit(x / y);
+            if y > Self::ZERO {
+                Self::FRAC_PI_2 - angle
+            } else {
+                -Self::FRAC_PI_2 - angle
+            }
+        }
+    }
+}
+
+/// The arctangent for values in range [-1.0, 1.0], with an error below 1e-4 rad.
+fn atan_unit(z: Fixed) -> Fixed {
+    const COEFFS: [Fixed; 5] = [
+        Fixed(4095),
+        Fixed(-1353),
+        Fixed(738),
+        Fixed(-349),
+        Fixed(85),
+    ];
+    let z2 = z * z;
+
+    z * COEFFS
+        .iter()
+        .rev()
+        .fold(Fixed::ZERO, |acc, &coeff| acc * z2 + coeff)
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let product = self.0 as i64 * rhs.0 as i64;
+
+        Self::saturated((product + (1 << (FIXED_FRAC_BITS - 1))) >> FIXED_FRAC_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let dividend = (self.0 as i64) << FIXED_FRAC_BITS;
+        let divisor = rhs.0 as i64;
+        if divisor == 0 {
+            return Self::saturated(dividend.signum() * i64::MAX);
+        }
+        // Round half away from zero
+        let half = if (dividend < 0) == (divisor < 0) {
+            divisor.abs() / 2
+        } else {
+            -(divisor.abs() / 2)
+        };
+
+        Self::saturated((dividend + half) / divisor)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.saturating_neg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fixed;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn fixed_functions() {
+        for i in -40..=40 {
+            let x = i as f32 * 0.17;
+            let fixed = Fixed::from_f32(x);
+
+            assert_abs_diff_eq!(fixed.sin().to_f32(), libm::sinf(x), epsilon = 1e-3);
+            assert_abs_diff_eq!(fixed.cos().to_f32(), libm::cosf(x), epsilon = 1e-3);
+            assert_abs_diff_eq!(fixed.atan().to_f32(), libm::atanf(x), epsilon = 1e-3);
+            assert_abs_diff_eq!(
+                fixed.atan2(Fixed::from_f32(-1.3)).to_f32(),
+                libm::atan2f(x, -1.3),
+                epsilon = 1e-3
+            );
+            assert_abs_diff_eq!(
+                Fixed::from_f32(x * 500.0)
+                    .hypot(Fixed::from_int(3000))
+                    .to_f32(),
+                libm::hypotf(x * 500.0, 3000.0),
+                epsilon = 1e-2
+            );
+        }
+        for x in [
+            0.0, -0.0, 1.0, -1.5, 0.3, 1e-4, -1e-4, 0.000122, 3999.9, -524287.0, 1e-30,
+        ] {
+            assert_eq!(
+                Fixed::from_f32(x).to_bits(),
+                libm::roundf(x * 4096.0) as i32,
+                "{x}"
+            );
+        }
+        assert_eq!(Fixed::from_f32(1e9).to_bits(), i32::MAX);
+        assert_eq!(Fixed::from_f32(f32::NEG_INFINITY).to_bits(), -i32::MAX);
+        assert_eq!(Fixed::from_f32(f32::NAN), Fixed::ZERO);
+        assert_abs_diff_eq!(
+            Fixed::from_int(2).sqrt().to_f32(),
+            2.0_f32.sqrt(),
+            epsilon = 1e-3
+        );
+        assert_abs_diff_eq!(
+            (Fixed::from_f32(-3.3) * Fixed::from_f32(2.5) / Fixed::from_f32(0.7)).to_f32(),
+            -3.3 * 2.5 / 0.7,
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn fixed_saturation() {
+        let max = Fixed::from_bits(i32::MAX);
+        let min = Fixed::from_bits(i32::MIN);
+
+        assert_eq!(Fixed::from_int(100_000) * Fixed::from_int(100_000), max);
+        assert_eq!(Fixed::from_int(-100_000) * Fixed::from_int(100_000), min);
+        assert_eq!(Fixed::from_int(100_000) / Fixed::from_f32(0.001), max);
+        assert_eq!(max + Fixed::ONE, max);
+        assert_eq!(min - Fixed::ONE, min);
+        assert_eq!(-min, max);
+        // A division by zero saturates towards the sign of the dividend
+        assert_eq!(Fixed::ONE / Fixed::ZERO, max);
+        assert_eq!(-Fixed::ONE / Fixed::ZERO, min);
+        assert_eq!(Fixed::ZERO / Fixed::ZERO, Fixed::ZERO);
+    }
+}
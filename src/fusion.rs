@@ -0,0 +1,477 @@
+//! Multi-sensor Fusion.
+//!
+//! All distance values are considered to be in millimeter.
+
+use crate::calibration::ZoneCalibration;
+use crate::math::{CoordsCartesian, CoordsSpherical};
+use crate::measurements::{HandFeatures, SensorParams};
+use crate::preprocessing::BackgroundModel;
+use crate::recognizer::{self, GestureHistory, HandPosSmoother, HistoryEntry, SensorPipeline};
+use crate::tracker::HandTracker;
+use crate::{
+    Gesture, HandState, RecognizerError, RecognizerParams, RecognizerResult, RecognizerStatus,
+    SensorMeasurement,
+};
+
+/// A gesture recognizer fusing the measurements of multiple sensors, e.g. mounted side by side to cover a wider area.
+///
+/// Every sensor is placed in a shared user-facing frame through the pose of its sensor parameters.
+/// The hands found by the sensors are fused into one hand, so gestures crossing from the FOV
+/// of one sensor into the FOV of another are recognized as one continuous gesture.
+/// The hands found by the sensors within [RecognizerParams::fusion_max_hand_dist] of the nearest hand are fused,
+/// the others are ignored. The presence mode is not supported.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FusionGestureRecognizer<
+    const RES_X: usize,
+    const RES_Y: usize,
+    const HISTORY_SIZE: usize,
+    const SENSORS: usize,
+> {
+    params: RecognizerParams,
+    start_time: u32,
+    pipelines: [SensorPipeline<RES_X, RES_Y>; SENSORS],
+    history: GestureHistory<RES_X, RES_Y, HISTORY_SIZE>,
+    smoother: HandPosSmoother,
+    tracker: HandTracker,
+}
+
+impl<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize, const SENSORS: usize>
+    FusionGestureRecognizer<RES_X, RES_Y, HISTORY_SIZE, SENSORS>
+{
+    /// A new fusion gesture recognizer with the given parameters and the sensor parameters of every sensor.
+    ///
+    /// Returns [RecognizerError::InitFailure] if the presence mode is enabled or if the mounting orientation
+    /// of a sensor is not supported for the resolution, see [SensorParams::is_orientation_supported].
+    pub fn new(
+        params: RecognizerParams,
+        sensor_params: [SensorParams; SENSORS],
+    ) -> Result<Self, RecognizerError> {
+        if !Self::is_supported(&params, &sensor_params) {
+            return Err(RecognizerError::InitFailure);
+        }
+
+        Ok(Self {
+            params,
+            start_time: 0,
+            pipelines: sensor_params
+                .map(|sensor_params| SensorPipeline::new(&params, sensor_params)),
+            history: GestureHistory::new(),
+            smoother: HandPosSmoother::new(&params),
+            tracker: recognizer::new_tracker(&params),
+        })
+    }
+
+    /// Resets the gesture recognizer with the given parameters.
+    ///
+    /// Clears the history, ongoing predictions and resets the internal state,
+    /// including the adaptive thresholds and the zone filters.
+    /// The zone calibrations and the calibrated backgrounds are kept.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInitFailure] and keeps the current parameters and state
+    /// if the presence mode is enabled or if the mounting orientation of a sensor is not supported for the resolution.
+    pub fn reset(
+        &mut self,
+        params: RecognizerParams,
+        sensor_params: [SensorParams; SENSORS],
+        now: u32,
+    ) -> RecognizerStatus {
+        if !Self::is_supported(&params, &sensor_params) {
+            return RecognizerStatus::RecognizerStatusInitFailure;
+        }
+
+        self.params = params;
+        self.start_time = now;
+        self.history.clear();
+        self.smoother = HandPosSmoother::new(&params);
+        self.tracker = recognizer::new_tracker(&params);
+        for (pipeline, sensor_params) in self.pipelines.iter_mut().zip(sensor_params) {
+            pipeline.reset(&params, sensor_params);
+        }
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Whether the parameters are supported, the presence mode is not.
+    fn is_supported(params: &RecognizerParams, sensor_params: &[SensorParams; SENSORS]) -> bool {
+        !params.presence_mode
+            && sensor_params
+                .iter()
+                .all(|sensor_params| sensor_params.is_orientation_supported::<RES_X, RES_Y>())
+    }
+
+    /// Updates the gesture recognizer with new measurements of every sensor.
+    ///
+    /// The measurements are considered simultaneous, the time of the most recent measurement is used.
+    /// It must be monotonically increasing (usually coming from a systick timer).
+    pub fn update(
+        &mut self,
+        measurements: [SensorMeasurement<RES_X, RES_Y>; SENSORS],
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        *result = RecognizerResult::default();
+        let now = measurements.iter().map(|m| m.time_ms).max().unwrap_or(0);
+
+        if !self.history.is_valid_time(now) {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        }
+
+        let mut hand_states = [HandState::HandNotFound; SENSORS];
+        let mut zone_dists = [[[-1.0; RES_X]; RES_Y]; SENSORS];
+        let mut min_dists = [f32::MAX; SENSORS];
+
+        for (sensor, (pipeline, measurement)) in
+            self.pipelines.iter_mut().zip(measurements).enumerate()
+        {
+            let Some((measurement, unsubtracted, _)) =
+                pipeline.preprocess(&self.params, measurement, None)
+            else {
+                return RecognizerStatus::RecognizerStatusInvalidInput;
+            };
+
+            (_, min_dists[sensor]) = measurement.min_dist();
+            zone_dists[sensor] = measurement.zone_dist;
+            hand_states[sensor] =
+                pipeline.recognize_hand(&self.params, &measurement, &unsubtracted, None);
+        }
+
+        // The zones of the sensor with the nearest zone are stored in the history.
+        // The previous sensor is kept while its nearest zone is within the static hold tolerance,
+        // so a hand held in the overlap of two sensors does not alternate between them.
+        let nearest = (0..SENSORS)
+            .min_by(|&a, &b| min_dists[a].total_cmp(&min_dists[b]))
+            .unwrap_or(0);
+        let previous = self.history.latest_sensor();
+        let sensor = if previous < SENSORS
+            && min_dists[previous] <= min_dists[nearest] + self.params.static_hold_tolerance_dist
+        {
+            previous
+        } else {
+            nearest
+        };
+
+        let hand_state = fuse_hand_states(&hand_states, self.params.fusion_max_hand_dist);
+        let hand_state = self.smoother.smooth(hand_state, now);
+        let hand_state = recognizer::track_hand(&mut self.tracker, &self.params, hand_state, now);
+        self.history.push(HistoryEntry {
+            time_ms: now,
+            sensor,
+            zone_dist: zone_dists
+                .get(sensor)
+                .copied()
+                .unwrap_or([[-1.0; RES_X]; RES_Y]),
+            hand_state,
+        });
+        let threshold_dists = self
+            .pipelines
+            .each_ref()
+            .map(|pipeline| pipeline.gesture_threshold_dist(&self.params));
+        result.hand_state = hand_state;
+        result.gesture = self
+            .history
+            .recognize_gesture(&self.params, &threshold_dists, now);
+        if result.gesture != Gesture::GestureNone {
+            // The history was cleared, so the hand must not be predicted into the next gesture
+            self.tracker.reset();
+        }
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Gets the current configured gesture recognizer parameters.
+    pub fn params(&self) -> RecognizerParams {
+        self.params
+    }
+
+    /// Gets the current configured sensor parameters of a sensor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sensor` is out of bounds.
+    pub fn sensor_params(&self, sensor: usize) -> SensorParams {
+        self.pipelines[sensor].sensor_params
+    }
+
+    /// Gets the current furthest hand distance for gesture recognition of a sensor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sensor` is out of bounds.
+    pub fn gesture_threshold_dist(&self, sensor: usize) -> f32 {
+        self.pipelines[sensor].gesture_threshold_dist(&self.params)
+    }
+
+    /// Gets the per-zone range calibration of a sensor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sensor` is out of bounds.
+    pub fn calibration(&self, sensor: usize) -> ZoneCalibration<RES_X, RES_Y> {
+        self.pipelines[sensor].calibration
+    }
+
+    /// Sets the per-zone range calibration of a sensor.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInvalidInput] if `sensor` is out of bounds.
+    pub fn set_calibration(
+        &mut self,
+        sensor: usize,
+        calibration: ZoneCalibration<RES_X, RES_Y>,
+    ) -> RecognizerStatus {
+        let Some(pipeline) = self.pipelines.get_mut(sensor) else {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+        pipeline.calibration = calibration;
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Learns the static background of a sensor from a measurement with no hand present.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInvalidInput] if `sensor` is out of bounds.
+    pub fn calibrate_background(
+        &mut self,
+        sensor: usize,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+    ) -> RecognizerStatus {
+        let Some(pipeline) = self.pipelines.get_mut(sensor) else {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+
+        pipeline.calibrate_background(measurement)
+    }
+
+    /// Gets the calibrated background of a sensor, e.g. to store it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sensor` is out of bounds.
+    pub fn background(&self, sensor: usize) -> BackgroundModel<RES_X, RES_Y> {
+        self.pipelines[sensor].background
+    }
+
+    /// Sets the background of a sensor, e.g. a previously stored one.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInvalidInput] if `sensor` is out of bounds.
+    pub fn set_background(
+        &mut self,
+        sensor: usize,
+        background: BackgroundModel<RES_X, RES_Y>,
+    ) -> RecognizerStatus {
+        let Some(pipeline) = self.pipelines.get_mut(sensor) else {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+        pipeline.background = background;
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Clears the background of a sensor.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInvalidInput] if `sensor` is out of bounds.
+    pub fn clear_background(&mut self, sensor: usize) -> RecognizerStatus {
+        self.set_background(sensor, BackgroundModel::empty())
+    }
+
+    /// Gets the hand tracker, providing the estimated hand position and velocity in the shared frame.
+    ///
+    /// Only updated when the tracker is enabled in the parameters.
+    pub fn tracker(&self) -> &HandTracker {
+        &self.tracker
+    }
+}
+
+/// Fuses the hands found by multiple sensors into one hand.
+///
+/// Only the hands within `max_dist` of the nearest hand are fused, so two separate objects do not produce
+/// a phantom hand between them. Their positions are averaged, weighted by the number of hand zones.
+/// The features are taken from the sensor that sees the most hand zones.
+fn fuse_hand_states(hand_states: &[HandState], max_dist: f32) -> HandState {
+    let Some(nearest) = hand_states
+        .iter()
+        .filter_map(|hand_state| match hand_state {
+            HandState::HandFound { hand_pos, .. } => Some(*hand_pos),
+            _ => None,
+        })
+        .min_by(|a, b| a.r.total_cmp(&b.r))
+        .map(CoordsCartesian::from)
+    else {
+        return HandState::HandNotFound;
+    };
+    let mut pos_sum = CoordsCartesian::zero();
+    let mut weight_sum = 0.0;
+    let mut features = HandFeatures::empty();
+
+    for hand_state in hand_states {
+        let HandState::HandFound {
+            hand_pos,
+            hand_features,
+        } = *hand_state
+        else {
+            continue;
+        };
+        let pos = CoordsCartesian::from(hand_pos);
+        if pos.dist_to(&nearest) > max_dist {
+            continue;
+        }
+        let weight = hand_features.zones.max(1) as f32;
+
+        if weight_sum == 0.0 || hand_features.zones > features.zones {
+            features = hand_features;
+        }
+        pos_sum.x += pos.x * weight;
+        pos_sum.y += pos.y * weight;
+        pos_sum.z += pos.z * weight;
+        weight_sum += weight;
+    }
+
+    HandState::HandFound {
+        hand_pos: CoordsSpherical::from(CoordsCartesian {
+            x: pos_sum.x / weight_sum,
+            y: pos_sum.y / weight_sum,
+            z: pos_sum.z / weight_sum,
+        }),
+        hand_features: features,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuse_hand_states, FusionGestureRecognizer};
+    use crate::math::{CoordsCartesian, CoordsSpherical};
+    use crate::measurements::{HandFeatures, SensorParams};
+    use crate::{
+        Gesture, HandState, RecognizerError, RecognizerParams, RecognizerResult, SensorMeasurement,
+    };
+    use approx::assert_relative_eq;
+
+    /// A hand at 200mm, that is seen by the zone at the given column of the measurement.
+    fn hand_at_column(column: Option<usize>, time_ms: u32) -> SensorMeasurement<4, 4> {
+        let mut measurement = SensorMeasurement::<4, 4>::invalid();
+        measurement.time_ms = time_ms;
+
+        if let Some(column) = column {
+            for row in measurement.zone_dist.iter_mut() {
+                row[column] = 200.0;
+            }
+        }
+
+        measurement
+    }
+
+    #[test]
+    fn fusion_swipe_across_sensors() {
+        let mut left = SensorParams::default_vl53l5cx();
+        left.pose_translation.y = -80.0;
+        let mut right = SensorParams::default_vl53l5cx();
+        right.pose_translation.y = 80.0;
+        let mut recognizer =
+            FusionGestureRecognizer::<4, 4, 60, 2>::new(RecognizerParams::default(), [left, right])
+                .unwrap();
+        let mut result = RecognizerResult::default();
+        let mut gestures = [Gesture::GestureNone; 30];
+
+        // Hand moves from the left sensor to the right sensor, never fully seen by one of them
+        for (i, gesture) in gestures.iter_mut().enumerate() {
+            let time_ms = 10 + i as u32 * 33;
+            let (column_left, column_right) = match i {
+                0..=19 => (Some(1), None),
+                20..=22 => (Some(3), None),
+                23..=25 => (None, Some(0)),
+                _ => (None, Some(2)),
+            };
+
+            recognizer.update(
+                [
+                    hand_at_column(column_left, time_ms),
+                    hand_at_column(column_right, time_ms),
+                ],
+                &mut result,
+            );
+            *gesture = result.gesture;
+        }
+
+        assert!(gestures.contains(&Gesture::GestureSwipeRight));
+    }
+
+    #[test]
+    fn fusion_static_hold_in_overlap() {
+        let mut left = SensorParams::default_vl53l5cx();
+        left.pose_translation.y = -80.0;
+        let mut right = SensorParams::default_vl53l5cx();
+        right.pose_translation.y = 80.0;
+        let mut recognizer =
+            FusionGestureRecognizer::<4, 4, 60, 2>::new(RecognizerParams::default(), [left, right])
+                .unwrap();
+        let mut result = RecognizerResult::default();
+        let mut gestures = [Gesture::GestureNone; 60];
+
+        // A hand held in front of both sensors, the nearest sensor alternates with the noise
+        for (i, gesture) in gestures.iter_mut().enumerate() {
+            let time_ms = 10 + i as u32 * 33;
+            let noise = if i % 2 == 0 { 5.0 } else { -5.0 };
+            let mut measurements = [SensorMeasurement::<4, 4>::new([[200.0; 4]; 4]); 2];
+            measurements[0].zone_dist[0][0] += noise;
+            measurements[1].zone_dist[0][0] -= noise;
+            for measurement in measurements.iter_mut() {
+                measurement.time_ms = time_ms;
+            }
+
+            recognizer.update(measurements, &mut result);
+            *gesture = result.gesture;
+        }
+
+        assert!(gestures.contains(&Gesture::GestureStaticHold));
+    }
+
+    #[test]
+    fn fusion_only_near_hands() {
+        let hand = |x: f32, y: f32, zones: u32| HandState::HandFound {
+            hand_pos: CoordsSpherical::from(CoordsCartesian { x, y, z: 0.0 }),
+            hand_features: HandFeatures {
+                zones,
+                ..HandFeatures::empty()
+            },
+        };
+        let fused_pos = |hand_state| {
+            let HandState::HandFound { hand_pos, .. } = hand_state else {
+                panic!("hand should be found");
+            };
+            CoordsCartesian::from(hand_pos)
+        };
+
+        // A hand and a farther object are not averaged into a phantom hand between them
+        let pos = fused_pos(fuse_hand_states(
+            &[hand(200.0, -50.0, 4), hand(600.0, 300.0, 12)],
+            150.0,
+        ));
+        assert_relative_eq!(pos.x, 200.0, epsilon = 0.01);
+        assert_relative_eq!(pos.y, -50.0, epsilon = 0.01);
+
+        // The same hand seen by both sensors is averaged
+        let pos = fused_pos(fuse_hand_states(
+            &[hand(200.0, -50.0, 4), hand(200.0, 50.0, 4)],
+            150.0,
+        ));
+        assert_relative_eq!(pos.y, 0.0, epsilon = 0.01);
+
+        assert!(matches!(
+            fuse_hand_states(&[HandState::HandNotFound; 2], 150.0),
+            HandState::HandNotFound
+        ));
+    }
+
+    #[test]
+    fn fusion_presence_mode_rejected() {
+        let params = RecognizerParams {
+            presence_mode: true,
+            ..Default::default()
+        };
+        let sensor_params = [SensorParams::default_vl53l5cx(); 2];
+
+        assert!(matches!(
+            FusionGestureRecognizer::<4, 4, 60, 2>::new(params, sensor_params),
+            Err(RecognizerError::InitFailure)
+        ));
+    }
+}
@@ -5,25 +5,63 @@
 //!
 //! A no-std no-alloc gesture recognition library for low resolution TOF-Sensors.
 
-// Only enable std for tests
-#![cfg_attr(not(test), no_std)]
+// Only enable std for tests and the std feature
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+/// Logs a trace message with `defmt` when the `defmt` feature is enabled, else does nothing.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::trace!($($arg)*);
+    };
+}
 
 // Modules
+pub mod calibration;
 pub mod cbind;
+pub mod fixed;
+pub mod fusion;
 pub mod math;
 pub mod measurements;
+pub mod preprocessing;
+pub mod presence;
+pub mod presets;
 pub mod recognizer;
+pub mod runtime_res;
+#[cfg(feature = "serde")]
+mod serde_matrix;
+pub mod tracker;
 
 // Re-exports
+pub use fixed::Fixed;
+pub use fusion::FusionGestureRecognizer;
+pub use measurements::HandFeatures;
+pub use measurements::HandPose;
 pub use measurements::HandState;
+pub use measurements::MountingRotation;
+pub use measurements::MultiTargetMeasurement;
+pub use measurements::OpticalModel;
 pub use measurements::SensorMeasurement;
+pub use measurements::ZoneQuality;
+pub use preprocessing::ZoneFilterMode;
+pub use presence::PresenceEvent;
+pub use presets::SensorPreset;
+pub use presets::SensorPresetId;
 pub use recognizer::Gesture;
 pub use recognizer::GestureRecognizer;
+pub use recognizer::HandPosFilter;
+pub use recognizer::RecognizerError;
 pub use recognizer::RecognizerParams;
 pub use recognizer::RecognizerResult;
 pub use recognizer::RecognizerStatus;
+pub use recognizer::ThresholdMode;
+pub use runtime_res::RuntimeResGestureRecognizer;
+pub use tracker::HandTracker;
 
-#[cfg(not(test))]
+/// The panic handler for the static library of the C bindings, built with `cargo rustc --crate-type staticlib`.
+///
+/// It is never defined with the `std` feature.
+#[cfg(all(not(test), not(feature = "std"), feature = "panic-handler"))]
 #[panic_handler]
 fn panic(_panic: &core::panic::PanicInfo<'_>) -> ! {
     loop {}
@@ -1,11 +1,115 @@
 //! Math Utilities.
 
 use core::iter::Sum;
-use core::ops::{Add, Div, Sub};
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+use crate::fixed::Fixed;
+
+/// The real number type of the per-zone computations of the recognizer.
+///
+/// It is [Fixed] when the `fixed-point` feature is enabled, else `f32`.
+#[cfg(not(feature = "fixed-point"))]
+pub type Scalar = f32;
+
+/// The real number type of the per-zone computations of the recognizer.
+///
+/// It is [Fixed] when the `fixed-point` feature is enabled, else `f32`.
+#[cfg(feature = "fixed-point")]
+pub type Scalar = Fixed;
+
+/// A real number type, implemented for `f32` and [Fixed].
+pub trait Real:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Zero.
+    const ZERO: Self;
+    /// One.
+    const ONE: Self;
+
+    /// Converts a float.
+    fn from_f32(value: f32) -> Self;
+    /// Converts an integer, which must be in range of the type.
+    fn from_i32(value: i32) -> Self;
+    /// Converts to a float.
+    fn to_f32(self) -> f32;
+    /// The square root.
+    fn sqrt(self) -> Self;
+    /// The length of the vector (self, other).
+    fn hypot(self, other: Self) -> Self;
+    /// The four quadrant arctangent of self (y) and other (x) (rad).
+    fn atan2(self, other: Self) -> Self;
+}
+
+impl Real for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn from_i32(value: i32) -> Self {
+        value as f32
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        libm::hypotf(self, other)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2f(self, other)
+    }
+}
+
+impl Real for Fixed {
+    const ZERO: Self = Fixed::ZERO;
+    const ONE: Self = Fixed::ONE;
+
+    fn from_f32(value: f32) -> Self {
+        Fixed::from_f32(value)
+    }
+
+    fn from_i32(value: i32) -> Self {
+        Fixed::from_int(value)
+    }
+
+    fn to_f32(self) -> f32 {
+        Fixed::to_f32(self)
+    }
+
+    fn sqrt(self) -> Self {
+        Fixed::sqrt(self)
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        Fixed::hypot(self, other)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        Fixed::atan2(self, other)
+    }
+}
 
 /// Cartesian coordinates.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct CoordsCartesian {
     /// The distance to the origin on the x-axis.
     pub x: f32,
@@ -43,12 +147,62 @@ impl CoordsCartesian {
                 + libm::powf(self.z - other.z, 2.0),
         )
     }
+
+    /// Rotates the coordinates with the given rotation matrix (`rotation * coords`).
+    pub fn rotated(&self, rotation: &[[f32; 3]; 3]) -> Self {
+        let [r0, r1, r2] = rotation;
+
+        Self {
+            x: r0[0] * self.x + r0[1] * self.y + r0[2] * self.z,
+            y: r1[0] * self.x + r1[1] * self.y + r1[2] * self.z,
+            z: r2[0] * self.x + r2[1] * self.y + r2[2] * self.z,
+        }
+    }
+}
+
+/// The identity rotation matrix.
+pub fn rotation_matrix_identity() -> [[f32; 3]; 3] {
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+}
+
+/// The rotation matrix from the given euler angles (rad), with the x-axis pointing forward, y right and z up.
+///
+/// - yaw: rotation around the z-axis, positive turns the x-axis to the right
+/// - pitch: rotation around the y-axis, positive tilts the x-axis upwards
+/// - roll: rotation around the x-axis, positive tilts the y-axis upwards
+///
+/// The roll is applied first, then the pitch and the yaw.
+pub fn rotation_matrix_from_euler(yaw: f32, pitch: f32, roll: f32) -> [[f32; 3]; 3] {
+    let (sy, cy) = (libm::sinf(yaw), libm::cosf(yaw));
+    let (sp, cp) = (libm::sinf(pitch), libm::cosf(pitch));
+    let (sr, cr) = (libm::sinf(roll), libm::cosf(roll));
+
+    let r_yaw = [[cy, -sy, 0.0], [sy, cy, 0.0], [0.0, 0.0, 1.0]];
+    let r_pitch = [[cp, 0.0, -sp], [0.0, 1.0, 0.0], [sp, 0.0, cp]];
+    let r_roll = [[1.0, 0.0, 0.0], [0.0, cr, -sr], [0.0, sr, cr]];
+
+    matrix_3d_mul(&r_yaw, &matrix_3d_mul(&r_pitch, &r_roll))
+}
+
+/// Multiplies two 3x3 matrices (`a * b`).
+pub fn matrix_3d_mul(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut product = [[0.0; 3]; 3];
+
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+
+    product
 }
 
 /// Represents spherical coordinates in mathematical naming convention.
 /// ([Reference](https://mathworld.wolfram.com/SphericalCoordinates.html))
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct CoordsSpherical {
     /// Distance to the origin.
     pub r: f32,
@@ -113,7 +267,7 @@ where
     transposed
 }
 
-/// Rotates the matrix 90 degrees in clockwise direction.
+/// Rotates the matrix 90 degrees in clockwise direction, with the first row at the top.
 pub fn matrix_2d_rotate_90deg<const ROWS: usize, const COLS: usize, T>(
     matrix: [[T; COLS]; ROWS],
 ) -> [[T; ROWS]; COLS]
@@ -133,7 +287,7 @@ where
     matrix_2d_reverse_rows(matrix_2d_reverse_cols(matrix))
 }
 
-/// Rotates the matrix 270 degrees in clockwise direction.
+/// Rotates the matrix 270 degrees in clockwise direction, with the first row at the top.
 pub fn matrix_2d_rotate_270deg<const ROWS: usize, const COLS: usize, T>(
     matrix: [[T; COLS]; ROWS],
 ) -> [[T; ROWS]; COLS]
@@ -169,6 +323,129 @@ where
     matrix
 }
 
+/// Downsamples the matrix by taking the minimum of the valid values (> 0.0) that each output value covers.
+///
+/// Output values that cover no valid value are set to -1.0.
+pub fn matrix_2d_pool_min_valid<
+    const ROWS: usize,
+    const COLS: usize,
+    const OUT_ROWS: usize,
+    const OUT_COLS: usize,
+>(
+    matrix: [[f32; COLS]; ROWS],
+) -> [[f32; OUT_COLS]; OUT_ROWS] {
+    matrix_2d_pool_valid(matrix, |values| values.fold(f32::MAX, f32::min))
+}
+
+/// Downsamples the matrix by averaging the valid values (> 0.0) that each output value covers.
+///
+/// Output values that cover no valid value are set to -1.0.
+pub fn matrix_2d_pool_mean_valid<
+    const ROWS: usize,
+    const COLS: usize,
+    const OUT_ROWS: usize,
+    const OUT_COLS: usize,
+>(
+    matrix: [[f32; COLS]; ROWS],
+) -> [[f32; OUT_COLS]; OUT_ROWS] {
+    matrix_2d_pool_valid(matrix, |values| {
+        let (sum, n) = values.fold((0.0, 0), |(sum, n), v| (sum + v, n + 1));
+        sum / n as f32
+    })
+}
+
+/// Pools the valid values (> 0.0) that each output value covers, with at least one value covered.
+fn matrix_2d_pool_valid<
+    const ROWS: usize,
+    const COLS: usize,
+    const OUT_ROWS: usize,
+    const OUT_COLS: usize,
+>(
+    matrix: [[f32; COLS]; ROWS],
+    pool: impl Fn(&mut dyn Iterator<Item = f32>) -> f32,
+) -> [[f32; OUT_COLS]; OUT_ROWS] {
+    let mut pooled = [[-1.0; OUT_COLS]; OUT_ROWS];
+
+    for (i, row) in pooled.iter_mut().enumerate() {
+        let rows = (i * ROWS / OUT_ROWS)..((i + 1) * ROWS).div_ceil(OUT_ROWS);
+
+        for (j, value) in row.iter_mut().enumerate() {
+            let cols = (j * COLS / OUT_COLS)..((j + 1) * COLS).div_ceil(OUT_COLS);
+            let mut values = matrix[rows.clone()]
+                .iter()
+                .flat_map(|r| r[cols.clone()].iter().copied())
+                .filter(|&v| v > 0.0)
+                .peekable();
+
+            if values.peek().is_some() {
+                *value = pool(&mut values);
+            }
+        }
+    }
+
+    pooled
+}
+
+/// Upsamples the matrix with bilinear interpolation of the valid values (> 0.0).
+///
+/// Output values are set to -1.0 when the nearest input value is invalid,
+/// else they are interpolated between the valid neighboring input values.
+/// All output values are -1.0 for an empty matrix.
+pub fn matrix_2d_upsample_bilinear_valid<
+    const ROWS: usize,
+    const COLS: usize,
+    const OUT_ROWS: usize,
+    const OUT_COLS: usize,
+>(
+    matrix: [[f32; COLS]; ROWS],
+) -> [[f32; OUT_COLS]; OUT_ROWS] {
+    let mut upsampled = [[-1.0; OUT_COLS]; OUT_ROWS];
+    if ROWS == 0 || COLS == 0 {
+        return upsampled;
+    }
+
+    /// The two neighboring input indices and the weight of the second, for the center of the output index.
+    fn neighbors(i: usize, len: usize, out_len: usize) -> (usize, usize, f32) {
+        let pos =
+            ((i as f32 + 0.5) * len as f32 / out_len as f32 - 0.5).clamp(0.0, (len - 1) as f32);
+        let i0 = pos as usize;
+
+        (i0, (i0 + 1).min(len - 1), pos - i0 as f32)
+    }
+
+    for (i, row) in upsampled.iter_mut().enumerate() {
+        let (i0, i1, wi) = neighbors(i, ROWS, OUT_ROWS);
+
+        for (j, value) in row.iter_mut().enumerate() {
+            let (j0, j1, wj) = neighbors(j, COLS, OUT_COLS);
+            let nearest = matrix[if wi < 0.5 { i0 } else { i1 }][if wj < 0.5 { j0 } else { j1 }];
+            if nearest <= 0.0 {
+                continue;
+            }
+
+            let (sum, weight_sum) = [
+                (matrix[i0][j0], (1.0 - wi) * (1.0 - wj)),
+                (matrix[i0][j1], (1.0 - wi) * wj),
+                (matrix[i1][j0], wi * (1.0 - wj)),
+                (matrix[i1][j1], wi * wj),
+            ]
+            .into_iter()
+            .filter(|&(v, _)| v > 0.0)
+            .fold((0.0, 0.0), |(sum, weight_sum), (v, w)| {
+                (sum + v * w, weight_sum + w)
+            });
+
+            *value = if weight_sum > 0.0 {
+                sum / weight_sum
+            } else {
+                nearest
+            };
+        }
+    }
+
+    upsampled
+}
+
 /// Iterator adapter that calculates the moving average.
 #[derive(Debug, Clone)]
 pub struct MovingAvg<I, T>
@@ -235,10 +512,185 @@ where
 {
 }
 
+/// Streaming exponential moving average filter.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EmaFilter {
+    alpha: f32,
+    value: f32,
+    initialized: bool,
+}
+
+impl EmaFilter {
+    /// A new exponential moving average filter.
+    ///
+    /// The smoothing factor `alpha` must be in range `(0.0, 1.0]`, where `1.0` means no smoothing.
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha,
+            value: 0.0,
+            initialized: false,
+        }
+    }
+
+    /// Resets the filter, the next value is then passed through unfiltered.
+    pub fn reset(&mut self) {
+        self.initialized = false;
+    }
+
+    /// Filters the next value.
+    pub fn filter(&mut self, value: f32) -> f32 {
+        if self.initialized {
+            self.value += self.alpha * (value - self.value);
+        } else {
+            self.value = value;
+            self.initialized = true;
+        }
+        self.value
+    }
+}
+
+/// Streaming one-euro filter, a low-pass filter with a cutoff frequency adapting to the speed of the signal.
+///
+/// Slow changes are smoothed heavily, fast changes pass with low latency.
+/// ([Reference](https://gery.casiez.net/1euro/))
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OneEuroFilter {
+    min_cutoff: f32,
+    beta: f32,
+    d_cutoff: f32,
+    value: f32,
+    deriv: f32,
+    time_ms: u32,
+    initialized: bool,
+}
+
+impl OneEuroFilter {
+    /// A new one-euro filter.
+    ///
+    /// Arguments:
+    /// - min_cutoff: the minimum cutoff frequency (Hz)
+    /// - beta: how much the cutoff frequency increases with the speed of the signal
+    /// - d_cutoff: the cutoff frequency for the speed of the signal (Hz)
+    pub fn new(min_cutoff: f32, beta: f32, d_cutoff: f32) -> Self {
+        Self {
+            min_cutoff,
+            beta,
+            d_cutoff,
+            value: 0.0,
+            deriv: 0.0,
+            time_ms: 0,
+            initialized: false,
+        }
+    }
+
+    /// Resets the filter, the next value is then passed through unfiltered.
+    pub fn reset(&mut self) {
+        self.initialized = false;
+    }
+
+    /// Filters the next value at the given time. The time must be monotonically increasing.
+    pub fn filter(&mut self, value: f32, time_ms: u32) -> f32 {
+        /// The smoothing factor of a low-pass filter with the given cutoff frequency and sampling period.
+        fn alpha(cutoff: f32, dt: f32) -> f32 {
+            let tau = 1.0 / (2.0 * core::f32::consts::PI * cutoff);
+            1.0 / (1.0 + tau / dt)
+        }
+
+        let dt = time_ms.saturating_sub(self.time_ms) as f32 / 1000.0;
+
+        if !self.initialized || dt <= 0.0 {
+            if !self.initialized {
+                self.value = value;
+                self.deriv = 0.0;
+            }
+            self.time_ms = time_ms;
+            self.initialized = true;
+            return self.value;
+        }
+
+        let deriv = (value - self.value) / dt;
+        self.deriv += alpha(self.d_cutoff, dt) * (deriv - self.deriv);
+
+        let cutoff = self.min_cutoff + self.beta * libm::fabsf(self.deriv);
+        self.value += alpha(cutoff, dt) * (value - self.value);
+        self.time_ms = time_ms;
+
+        self.value
+    }
+}
+
+/// Streaming median filter over a window of the last values.
+///
+/// `N` is the capacity, the window size can be configured up to it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct MedianFilter<const N: usize> {
+    window: [f32; N],
+    window_size: usize,
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> MedianFilter<N> {
+    /// A new median filter. The window size is clamped to range `[1, N]`.
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            window: [0.0; N],
+            window_size: window_size.clamp(1, N.max(1)),
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Resets the filter, clearing the window.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Filters the next value.
+    ///
+    /// Returns the median of the values in the window. For an even number of values the mean of the two middle values.
+    pub fn filter(&mut self, value: f32) -> f32 {
+        if N == 0 {
+            return value;
+        }
+        self.window[self.next] = value;
+        self.next = (self.next + 1) % self.window_size;
+        self.len = (self.len + 1).min(self.window_size);
+
+        let mut window = self.window;
+        median(&mut window[..self.len])
+    }
+}
+
+/// The median of the values, which are sorted in place. For an even number of values the mean of the two middle values.
+///
+/// Returns 0.0 for no values.
+pub(crate) fn median(values: &mut [f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        (values[mid - 1] + values[mid]) / 2.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{CoordsCartesian, CoordsSpherical, MovingAvgIter};
+    use super::{
+        median, CoordsCartesian, CoordsSpherical, EmaFilter, MedianFilter, MovingAvgIter,
+        OneEuroFilter,
+    };
     use approx::assert_relative_eq;
+    use core::f32::consts::FRAC_PI_6;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -271,6 +723,71 @@ mod tests {
         assert_relative_eq!(coords_cart.z, 2.0);
     }
 
+    #[test]
+    fn rotation_from_euler() {
+        let forward = CoordsCartesian {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let right = CoordsCartesian {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        };
+
+        // Tilted upwards by 30 degrees
+        let tilted = forward.rotated(&super::rotation_matrix_from_euler(0.0, FRAC_PI_6, 0.0));
+        assert_relative_eq!(tilted.x, 0.8660254);
+        assert_relative_eq!(tilted.y, 0.0);
+        assert_relative_eq!(tilted.z, 0.5);
+
+        // Turned to the right by 30 degrees
+        let turned = forward.rotated(&super::rotation_matrix_from_euler(FRAC_PI_6, 0.0, 0.0));
+        assert_relative_eq!(turned.y, 0.5);
+
+        // Rolled by 30 degrees
+        let rolled = right.rotated(&super::rotation_matrix_from_euler(0.0, 0.0, FRAC_PI_6));
+        assert_relative_eq!(rolled.y, 0.8660254);
+        assert_relative_eq!(rolled.z, 0.5);
+    }
+
+    #[test]
+    fn matrix_2d_pool_valid() {
+        let m = [[100.0, 200.0, -1.0, -1.0], [300.0, 400.0, -1.0, 500.0]];
+
+        assert_eq!(
+            super::matrix_2d_pool_min_valid::<2, 4, 1, 2>(m),
+            [[100.0, 500.0]]
+        );
+        assert_eq!(
+            super::matrix_2d_pool_mean_valid::<2, 4, 1, 2>(m),
+            [[250.0, 500.0]]
+        );
+        assert_eq!(
+            super::matrix_2d_pool_mean_valid::<2, 4, 2, 2>(m),
+            [[150.0, -1.0], [350.0, 500.0]]
+        );
+    }
+
+    #[test]
+    fn matrix_2d_upsample_bilinear_valid() {
+        let m = [[100.0, 200.0, -1.0]];
+
+        assert_eq!(
+            super::matrix_2d_upsample_bilinear_valid::<1, 3, 1, 6>(m),
+            [[100.0, 125.0, 175.0, 200.0, -1.0, -1.0]]
+        );
+        assert_eq!(
+            super::matrix_2d_upsample_bilinear_valid::<0, 3, 2, 6>([]),
+            [[-1.0; 6]; 2]
+        );
+        assert_eq!(
+            super::matrix_2d_upsample_bilinear_valid::<2, 0, 2, 6>([[], []]),
+            [[-1.0; 6]; 2]
+        );
+    }
+
     #[test]
     fn matrix_2d_transpose() {
         let m = [
@@ -404,4 +921,46 @@ mod tests {
             assert_relative_eq!(expected[i], res, epsilon = 0.0001);
         }
     }
+
+    #[test]
+    fn ema_filter() {
+        let mut filter = EmaFilter::new(0.5);
+
+        assert_relative_eq!(filter.filter(4.0), 4.0);
+        assert_relative_eq!(filter.filter(8.0), 6.0);
+        assert_relative_eq!(filter.filter(8.0), 7.0);
+        filter.reset();
+        assert_relative_eq!(filter.filter(1.0), 1.0);
+    }
+
+    #[test]
+    fn one_euro_filter() {
+        let mut filter = OneEuroFilter::new(1.0, 0.0, 1.0);
+
+        assert_relative_eq!(filter.filter(0.0, 0), 0.0);
+        // Constant signal passes through unchanged
+        assert_relative_eq!(filter.filter(0.0, 50), 0.0);
+        // A step is smoothed, but follows over time
+        let first = filter.filter(10.0, 100);
+        assert!(first > 0.0 && first < 10.0);
+        let mut last = first;
+        for i in 3..100 {
+            last = filter.filter(10.0, i * 50);
+        }
+        assert_relative_eq!(last, 10.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn median_filter() {
+        let mut filter = MedianFilter::<5>::new(3);
+
+        assert_relative_eq!(filter.filter(1.0), 1.0);
+        assert_relative_eq!(filter.filter(3.0), 2.0);
+        assert_relative_eq!(filter.filter(100.0), 3.0);
+        assert_relative_eq!(filter.filter(4.0), 4.0);
+        assert_relative_eq!(filter.filter(5.0), 5.0);
+
+        assert_relative_eq!(median(&mut [7.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_relative_eq!(median(&mut []), 0.0);
+    }
 }
@@ -2,29 +2,268 @@
 
 use core::f32::consts::FRAC_PI_2;
 
-use crate::math::{CoordsCartesian, CoordsSpherical};
+use crate::math::{self, CoordsCartesian, CoordsSpherical, Real};
+use crate::RecognizerParams;
 
 /// The recognized hand state.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum HandState {
     /// No hand was found.
     HandNotFound,
     /// Hand was found with this position.
     HandFound {
-        /// The hand position in spherical coordinates.
+        /// The hand position in spherical coordinates, in the user-facing frame of the sensor pose.
         hand_pos: CoordsSpherical,
+        /// The size and shape features of the hand.
+        hand_features: HandFeatures,
     },
 }
 
+#[cfg(feature = "std")]
+impl std::fmt::Display for HandState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandState::HandNotFound => write!(f, "hand not found"),
+            HandState::HandFound {
+                hand_pos,
+                hand_features,
+            } => write!(
+                f,
+                "hand found at r: {:.1}mm, theta: {:.1}°, phi: {:.1}° ({:?}, {} zones)",
+                hand_pos.r,
+                hand_pos.theta.to_degrees(),
+                hand_pos.phi.to_degrees(),
+                hand_features.pose,
+                hand_features.zones
+            ),
+        }
+    }
+}
+
+/// The classified pose of the hand.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HandPose {
+    /// The pose could not be classified.
+    HandPoseUnknown = 0,
+    /// An open palm facing the sensor.
+    HandPoseOpenPalm,
+    /// A fist.
+    HandPoseFist,
+    /// A single finger pointing at the sensor.
+    HandPoseFinger,
+}
+
+/// Size and shape features of the recognized hand, computed from the zones that belong to it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HandFeatures {
+    /// The estimated physical area of the hand (mm^2).
+    pub area: f32,
+    /// The horizontal extent of the hand.
+    pub width: f32,
+    /// The vertical extent of the hand.
+    pub height: f32,
+    /// How compact the hand is in range `(0.0, 1.0]`, the ratio of its zones to the zones of its bounding box.
+    pub compactness: f32,
+    /// The number of zones that belong to the hand.
+    pub zones: u32,
+    /// The classified pose of the hand.
+    pub pose: HandPose,
+    /// The in-plane rotation angle of the principal axis of the hand (rad), in range `[-PI/2, PI/2]`.
+    ///
+    /// Measured from the horizontal axis, counterclockwise when looking at the sensor.
+    pub orientation: f32,
+    /// How elongated the hand is in range `[0.0, 1.0]`, `0.0` for round and `1.0` for line shaped hands.
+    ///
+    /// The orientation is only meaningful for elongated hands.
+    pub elongation: f32,
+}
+
+impl HandFeatures {
+    /// Features without any zones and an unknown pose.
+    pub fn empty() -> Self {
+        Self {
+            area: 0.0,
+            width: 0.0,
+            height: 0.0,
+            compactness: 0.0,
+            zones: 0,
+            pose: HandPose::HandPoseUnknown,
+            orientation: 0.0,
+            elongation: 0.0,
+        }
+    }
+}
+
+/// Quality information of a zone measurement, as reported by e.g. the ST VL53L5CX.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ZoneQuality {
+    /// The target status reported by the sensor, [ZoneQuality::TARGET_STATUS_UNKNOWN] if not available.
+    pub target_status: u8,
+    /// The signal rate per SPAD (kcps/SPAD), negative if not available.
+    pub signal_per_spad: f32,
+    /// The estimated standard deviation of the distance (mm), negative if not available.
+    pub range_sigma: f32,
+}
+
+impl ZoneQuality {
+    /// The target status value for an unknown target status.
+    pub const TARGET_STATUS_UNKNOWN: u8 = 255;
+
+    /// Unknown quality, the zone is considered fully confident.
+    pub fn unknown() -> Self {
+        Self {
+            target_status: Self::TARGET_STATUS_UNKNOWN,
+            signal_per_spad: -1.0,
+            range_sigma: -1.0,
+        }
+    }
+
+    /// Checks if the zone is accepted with the quality thresholds of the sensor parameters.
+    pub fn is_accepted(&self, params: &SensorParams) -> bool {
+        if self.target_status != Self::TARGET_STATUS_UNKNOWN
+            && (self.target_status >= 32
+                || params.quality_valid_target_status & (1 << self.target_status) == 0)
+        {
+            return false;
+        }
+        if self.signal_per_spad >= 0.0 && self.signal_per_spad < params.quality_min_signal_per_spad
+        {
+            return false;
+        }
+        if self.range_sigma >= 0.0 && self.range_sigma > params.quality_max_range_sigma {
+            return false;
+        }
+
+        true
+    }
+
+    /// The confidence of the zone in range `[0.0, 1.0]`, used to weight it.
+    ///
+    /// Zero for zones that are not accepted, else derived from the range sigma relative to the maximum range sigma.
+    pub fn confidence(&self, params: &SensorParams) -> f32 {
+        self.confidence_with::<f32>(params)
+    }
+
+    /// The confidence of the zone (see [Self::confidence]), computed with the given real number type.
+    pub(crate) fn confidence_with<T: Real>(&self, params: &SensorParams) -> T {
+        if !self.is_accepted(params) {
+            return T::ZERO;
+        }
+        if self.range_sigma < 0.0 || params.quality_max_range_sigma <= 0.0 {
+            return T::ONE;
+        }
+        let rel_sigma = T::from_f32(self.range_sigma) / T::from_f32(params.quality_max_range_sigma);
+
+        T::ONE / (T::ONE + rel_sigma * rel_sigma)
+    }
+}
+
+/// The maximum resolution in both directions for which zones can be masked.
+pub const ZONE_MASK_MAX_RES: usize = 8;
+
+impl Default for ZoneQuality {
+    fn default() -> Self {
+        Self::unknown()
+    }
+}
+
+/// The clockwise rotation that is applied to the zones when looking at the sensor, compensating the rotation of the
+/// sensor in its mounting.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MountingRotation {
+    /// The zones are not rotated.
+    MountingRotation0 = 0,
+    /// The zones are rotated by 90 degrees. Only supported for square resolutions.
+    MountingRotation90,
+    /// The zones are rotated by 180 degrees.
+    MountingRotation180,
+    /// The zones are rotated by 270 degrees. Only supported for square resolutions.
+    MountingRotation270,
+}
+
+/// The maximum resolution of the zone angle lookup table of the optical model.
+pub const OPTICAL_LUT_MAX_RES: usize = 8;
+
+/// The optical model, mapping a zone to the direction of its center.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OpticalModel {
+    /// Every zone covers an equal share of the FOV angle.
+    OpticalModelLinear = 0,
+    /// A pinhole lens with radial distortion, the zones are equally sized on the image plane.
+    ///
+    /// The normalized image coordinates are scaled by `1 + optical_distortion * r^2`.
+    OpticalModelPinhole,
+    /// The zone angles are looked up from a table, e.g. from the zone angle data of the sensor vendor.
+    ///
+    /// Zones outside of [OPTICAL_LUT_MAX_RES] fall back to [OpticalModel::OpticalModelLinear].
+    OpticalModelLookupTable,
+}
+
 /// Configurable sensor parameters. Different for every sensor.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SensorParams {
     /// The horizontal FOV of the sensor.
     pub fov_horizontal: f32,
     /// The vertical FOV of the sensor.
     pub fov_vertical: f32,
+    /// The optical model, mapping a zone to the direction of its center.
+    pub optical_model: OpticalModel,
+    /// The radial distortion coefficient of [OpticalModel::OpticalModelPinhole].
+    pub optical_distortion: f32,
+    /// The horizontal angle of every zone center for [OpticalModel::OpticalModelLookupTable] (deg), positive to the right.
+    ///
+    /// Indexed like the oriented zones.
+    pub optical_lut_angle_hor: [[f32; OPTICAL_LUT_MAX_RES]; OPTICAL_LUT_MAX_RES],
+    /// The vertical angle of every zone center for [OpticalModel::OpticalModelLookupTable] (deg), positive upwards.
+    ///
+    /// Indexed like the oriented zones, so the first row is the bottom row and has the lowest angles.
+    pub optical_lut_angle_vert: [[f32; OPTICAL_LUT_MAX_RES]; OPTICAL_LUT_MAX_RES],
+    /// The rotation of the zones, compensating the rotation of the sensor in its mounting.
+    pub mounting_rotation: MountingRotation,
+    /// Whether the zones are mirrored horizontally, applied after the rotation.
+    pub mounting_mirror_horizontal: bool,
+    /// Whether the zones are mirrored vertically, applied after the rotation.
+    pub mounting_mirror_vertical: bool,
+    /// The rotation from the sensor frame into the user-facing frame, in which the gestures are classified.
+    ///
+    /// The identity when the sensor directly faces the user, see [SensorParams::set_pose_euler].
+    pub pose_rotation: [[f32; 3]; 3],
+    /// The position of the sensor in the user-facing frame (mm), e.g. to place multiple sensors in a shared frame.
+    pub pose_translation: CoordsCartesian,
+    /// The mask of zones that are ignored, e.g. zones shadowed by the enclosure or pointed at a fixed obstacle.
+    ///
+    /// Bit `pos_y * 8 + pos_x` is set for the masked zone at the index \[pos_y\]\[pos_x\] of the oriented zones,
+    /// so only zones up to a resolution of [ZONE_MASK_MAX_RES] can be masked.
+    pub zone_mask: u64,
+    /// The accepted target status values of the zone quality. Bit `n` is set when target status `n` is accepted.
+    pub quality_valid_target_status: u32,
+    /// The minimum accepted signal rate per SPAD of the zone quality (kcps/SPAD).
+    pub quality_min_signal_per_spad: f32,
+    /// The maximum accepted range sigma of the zone quality (mm).
+    pub quality_max_range_sigma: f32,
+    /// Targets of multi-target measurements that are closer are skipped, e.g. reflections of a cover glass.
+    pub min_target_dist: f32,
 }
 
 impl SensorParams {
@@ -34,20 +273,197 @@ impl SensorParams {
             // The VL53L5CX has a diagonal FOV of 63deg, so fov_x = fov_y = 63.0 / sqrt(2) = 45.0
             fov_horizontal: 45.0,
             fov_vertical: 45.0,
+            optical_model: OpticalModel::OpticalModelLinear,
+            optical_distortion: 0.0,
+            optical_lut_angle_hor: [[0.0; OPTICAL_LUT_MAX_RES]; OPTICAL_LUT_MAX_RES],
+            optical_lut_angle_vert: [[0.0; OPTICAL_LUT_MAX_RES]; OPTICAL_LUT_MAX_RES],
+            mounting_rotation: MountingRotation::MountingRotation0,
+            mounting_mirror_horizontal: false,
+            mounting_mirror_vertical: false,
+            pose_rotation: math::rotation_matrix_identity(),
+            pose_translation: CoordsCartesian::zero(),
+            zone_mask: 0,
+            // Target status 5: range valid, 9: range valid with large pulse
+            quality_valid_target_status: (1 << 5) | (1 << 9),
+            quality_min_signal_per_spad: 0.0,
+            quality_max_range_sigma: 30.0,
+            min_target_dist: 0.0,
+        }
+    }
+
+    /// The default parameters for the ST VL53L7CX TOF-Sensor.
+    pub fn default_vl53l7cx() -> Self {
+        Self {
+            // The VL53L7CX has a diagonal FOV of 90deg, with fov_x = fov_y = 60.0
+            fov_horizontal: 60.0,
+            fov_vertical: 60.0,
+            ..Self::default_vl53l5cx()
+        }
+    }
+
+    /// The default parameters for the ST VL53L8CX TOF-Sensor.
+    pub fn default_vl53l8cx() -> Self {
+        Self {
+            // The VL53L8CX has a diagonal FOV of 65deg, so fov_x = fov_y = 65.0 / sqrt(2) = 46.0
+            fov_horizontal: 46.0,
+            fov_vertical: 46.0,
+            ..Self::default_vl53l5cx()
+        }
+    }
+
+    /// The default parameters for the ams TMF8820, TMF8821 and TMF8828 TOF-Sensors.
+    pub fn default_tmf882x() -> Self {
+        Self {
+            // The TMF882x has a diagonal FOV of 63deg, with fov_x = 52.0 and fov_y = 41.0
+            fov_horizontal: 52.0,
+            fov_vertical: 41.0,
+            // The TMF882x does not report a target status like the VL53LxCX
+            quality_valid_target_status: u32::MAX,
+            ..Self::default_vl53l5cx()
         }
     }
+
+    /// Checks if the mounting orientation is supported for the given resolution.
+    pub fn is_orientation_supported<const RES_X: usize, const RES_Y: usize>(&self) -> bool {
+        RES_X == RES_Y
+            || matches!(
+                self.mounting_rotation,
+                MountingRotation::MountingRotation0 | MountingRotation::MountingRotation180
+            )
+    }
+
+    /// The direction of the center of a zone as (horizontal, vertical) angles (rad).
+    ///
+    /// The horizontal angle is positive to the right, the vertical angle positive upwards.
+    /// Both increase with the zone position, the zone \[0\]\[0\] is the bottom left one.
+    pub fn zone_angles<const RES_X: usize, const RES_Y: usize>(
+        &self,
+        zone_pos_x: usize,
+        zone_pos_y: usize,
+    ) -> (f32, f32) {
+        // The zone center relative to the sensor center, in range [-1.0, 1.0]
+        let u = (zone_pos_x as f32 + 0.5 - RES_X as f32 / 2.0) / (RES_X as f32 / 2.0);
+        let v = (zone_pos_y as f32 + 0.5 - RES_Y as f32 / 2.0) / (RES_Y as f32 / 2.0);
+        let half_fov_hor = (self.fov_horizontal / 2.0).to_radians();
+        let half_fov_vert = (self.fov_vertical / 2.0).to_radians();
+
+        match self.optical_model {
+            OpticalModel::OpticalModelPinhole => {
+                let distortion = 1.0 + self.optical_distortion * (u * u + v * v);
+
+                (
+                    libm::atanf(u * distortion * libm::tanf(half_fov_hor)),
+                    libm::atanf(v * distortion * libm::tanf(half_fov_vert)),
+                )
+            }
+            OpticalModel::OpticalModelLookupTable
+                if zone_pos_x < OPTICAL_LUT_MAX_RES && zone_pos_y < OPTICAL_LUT_MAX_RES =>
+            {
+                (
+                    self.optical_lut_angle_hor[zone_pos_y][zone_pos_x].to_radians(),
+                    self.optical_lut_angle_vert[zone_pos_y][zone_pos_x].to_radians(),
+                )
+            }
+            _ => (u * half_fov_hor, v * half_fov_vert),
+        }
+    }
+
+    /// The angular size of a zone as (horizontal, vertical) angles (rad).
+    ///
+    /// Derived from the distances to the centers of the neighboring zones, so it follows the optical model.
+    pub(crate) fn zone_size_angles<const RES_X: usize, const RES_Y: usize>(
+        &self,
+        zone_pos_x: usize,
+        zone_pos_y: usize,
+    ) -> (f32, f32) {
+        let angles = |pos_x: usize, pos_y: usize| self.zone_angles::<RES_X, RES_Y>(pos_x, pos_y);
+        let (center_hor, center_vert) = angles(zone_pos_x, zone_pos_y);
+        // The distance between the centers of the previous and the next zone, halved if both exist
+        let size = |prev: Option<f32>, next: Option<f32>, center: f32, fov: f32, res: usize| {
+            libm::fabsf(match (prev, next) {
+                (Some(prev), Some(next)) => (next - prev) / 2.0,
+                (Some(prev), None) => center - prev,
+                (None, Some(next)) => next - center,
+                (None, None) => (fov / res as f32).to_radians(),
+            })
+        };
+
+        (
+            size(
+                zone_pos_x.checked_sub(1).map(|x| angles(x, zone_pos_y).0),
+                (zone_pos_x + 1 < RES_X).then(|| angles(zone_pos_x + 1, zone_pos_y).0),
+                center_hor,
+                self.fov_horizontal,
+                RES_X,
+            ),
+            size(
+                zone_pos_y.checked_sub(1).map(|y| angles(zone_pos_x, y).1),
+                (zone_pos_y + 1 < RES_Y).then(|| angles(zone_pos_x, zone_pos_y + 1).1),
+                center_vert,
+                self.fov_vertical,
+                RES_Y,
+            ),
+        )
+    }
+
+    /// Sets the pose of the sensor relative to the user from euler angles (deg).
+    ///
+    /// E.g. a pitch of 30 degrees for a sensor that is tilted upwards in a sloped panel.
+    /// See [math::rotation_matrix_from_euler] for the axes and directions.
+    pub fn set_pose_euler(&mut self, yaw: f32, pitch: f32, roll: f32) {
+        self.pose_rotation = math::rotation_matrix_from_euler(
+            yaw.to_radians(),
+            pitch.to_radians(),
+            roll.to_radians(),
+        );
+    }
+
+    /// Converts a position from the sensor frame into the user-facing frame.
+    pub fn to_user_frame(&self, pos: CoordsSpherical) -> CoordsSpherical {
+        let rotated = CoordsCartesian::from(pos).rotated(&self.pose_rotation);
+
+        CoordsSpherical::from(CoordsCartesian {
+            x: rotated.x + self.pose_translation.x,
+            y: rotated.y + self.pose_translation.y,
+            z: rotated.z + self.pose_translation.z,
+        })
+    }
+
+    /// Masks the zone at the given position, so that it is ignored.
+    ///
+    /// Positions outside of [ZONE_MASK_MAX_RES] are ignored.
+    pub fn mask_zone(&mut self, pos_x: usize, pos_y: usize) {
+        if pos_x < ZONE_MASK_MAX_RES && pos_y < ZONE_MASK_MAX_RES {
+            self.zone_mask |= 1 << (pos_y * ZONE_MASK_MAX_RES + pos_x);
+        }
+    }
+
+    /// Checks if the zone at the given position is masked.
+    pub fn is_zone_masked(&self, pos_x: usize, pos_y: usize) -> bool {
+        pos_x < ZONE_MASK_MAX_RES
+            && pos_y < ZONE_MASK_MAX_RES
+            && self.zone_mask & (1 << (pos_y * ZONE_MASK_MAX_RES + pos_x)) != 0
+    }
 }
 
 /// Represents a sensor measurement coming from the TOF sensor.
 ///
-/// Expects that the zones are already rotated and mirrored,
-/// so that the zone with index \[0\]\[0\] is the top left corner when looking at the sensor.
+/// The zones are expected in the order they are read out from the sensor.
+/// They are rotated and mirrored according to the mounting orientation of the [SensorParams] by the recognizer,
+/// so that afterwards the zone with index \[0\]\[0\] is the bottom left corner when looking at the sensor,
+/// with the rows going upwards and the columns going to the right.
+///
+/// Previous versions documented the zone \[0\]\[0\] as the top left corner. Integrations that reversed the row order
+/// for this have to remove that step, or set `mounting_mirror_vertical` of the [SensorParams].
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SensorMeasurement<const RES_X: usize, const RES_Y: usize> {
     /// The measured distances of each zone.
     ///
     /// Invalid distance measurements are represented by value -1.0.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_matrix"))]
     pub zone_dist: [[f32; RES_X]; RES_Y],
     /// The time of the measurement in milliseconds. Must be monotonically increasing.
     pub time_ms: u32,
@@ -64,35 +480,126 @@ impl<const RES_X: usize, const RES_Y: usize> SensorMeasurement<RES_X, RES_Y> {
 
     /// An invalid measurement. The time is set to zero, distances are set to invalid (value `-1.0`).
     pub fn invalid() -> Self {
-        Self {
-            zone_dist: [[-1.0; RES_X]; RES_Y],
-            time_ms: 0,
+        Self::new([[-1.0; RES_X]; RES_Y])
+    }
+
+    /// Creates a new measurement from a slice, e.g. from recorded data.
+    ///
+    /// Arguments:
+    /// - zone_dist: the measured distances of each zone in row-major order, invalid distances are represented by value -1.0.
+    /// - time_ms: the time of the measurement in milliseconds.
+    ///
+    /// Returns `None` if the length does not match the resolution.
+    #[cfg(feature = "std")]
+    pub fn from_slice(zone_dist: &[f32], time_ms: u32) -> Option<Self> {
+        if zone_dist.len() != RES_X * RES_Y {
+            return None;
+        }
+        let mut measurement = Self::invalid();
+        measurement.time_ms = time_ms;
+
+        for (i, &dist) in zone_dist.iter().enumerate() {
+            measurement.zone_dist[i / RES_X][i % RES_X] = dist;
+        }
+
+        Some(measurement)
+    }
+
+    /// Creates a new measurement from the rows of zone distances.
+    ///
+    /// Returns `None` if the number of rows or columns does not match the resolution.
+    #[cfg(feature = "std")]
+    pub fn from_rows(zone_dist: Vec<Vec<f32>>, time_ms: u32) -> Option<Self> {
+        if zone_dist.len() != RES_Y || zone_dist.iter().any(|row| row.len() != RES_X) {
+            return None;
         }
+
+        Self::from_slice(&zone_dist.concat(), time_ms)
+    }
+
+    /// Rotates and mirrors the zones according to the mounting orientation of the sensor parameters.
+    ///
+    /// Returns `None` if the orientation is not supported for the resolution, see [SensorParams::is_orientation_supported].
+    pub fn oriented(self, params: &SensorParams) -> Option<Self> {
+        Some(Self {
+            zone_dist: zones_oriented(self.zone_dist, params)?,
+            time_ms: self.time_ms,
+        })
+    }
+
+    /// Downsamples the measurement to a lower resolution, with the minimum valid distance of the covered zones.
+    pub fn downsampled_min<const OUT_X: usize, const OUT_Y: usize>(
+        &self,
+    ) -> SensorMeasurement<OUT_X, OUT_Y> {
+        SensorMeasurement {
+            time_ms: self.time_ms,
+            ..SensorMeasurement::new(math::matrix_2d_pool_min_valid(self.zone_dist))
+        }
+    }
+
+    /// Downsamples the measurement to a lower resolution, with the mean of the valid distances of the covered zones.
+    pub fn downsampled_mean<const OUT_X: usize, const OUT_Y: usize>(
+        &self,
+    ) -> SensorMeasurement<OUT_X, OUT_Y> {
+        SensorMeasurement {
+            time_ms: self.time_ms,
+            ..SensorMeasurement::new(math::matrix_2d_pool_mean_valid(self.zone_dist))
+        }
+    }
+
+    /// Upsamples the measurement to a higher resolution, with bilinear interpolation of the valid distances.
+    ///
+    /// Zones are invalid when the nearest zone of the measurement is invalid.
+    pub fn upsampled_bilinear<const OUT_X: usize, const OUT_Y: usize>(
+        &self,
+    ) -> SensorMeasurement<OUT_X, OUT_Y> {
+        SensorMeasurement {
+            time_ms: self.time_ms,
+            ..SensorMeasurement::new(math::matrix_2d_upsample_bilinear_valid(self.zone_dist))
+        }
+    }
+
+    /// Sets the distances of the zones that are not accepted by the quality thresholds to invalid (value `-1.0`).
+    ///
+    /// The zone quality must be in the same orientation as the measurement.
+    pub fn reject_low_quality(
+        mut self,
+        zone_quality: &[[ZoneQuality; RES_X]; RES_Y],
+        params: &SensorParams,
+    ) -> Self {
+        for (row, quality_row) in self.zone_dist.iter_mut().zip(zone_quality.iter()) {
+            for (dist, quality) in row.iter_mut().zip(quality_row.iter()) {
+                if !quality.is_accepted(params) {
+                    *dist = -1.0;
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Sets the distances of the masked zones to invalid (value `-1.0`).
+    pub fn mask_zones(mut self, params: &SensorParams) -> Self {
+        if params.zone_mask == 0 {
+            return self;
+        }
+
+        for (pos_y, row) in self.zone_dist.iter_mut().enumerate() {
+            for (pos_x, dist) in row.iter_mut().enumerate() {
+                if params.is_zone_masked(pos_x, pos_y) {
+                    *dist = -1.0;
+                }
+            }
+        }
+
+        self
     }
 
     /// Finds the position in the matrix and distance value of the zone with minimal distance.
     ///
     /// Returns the tuple: (["x-pos in matrix", "y-pos in matrix"], "distance").
     pub(crate) fn min_dist(&self) -> ([usize; 2], f32) {
-        self.zone_dist
-            .into_iter()
-            .enumerate()
-            .flat_map(|(y_pos, r)| {
-                r.into_iter().enumerate().filter_map(move |(x_pos, d)| {
-                    if d > 0.0 {
-                        Some(([x_pos, y_pos], d))
-                    } else {
-                        None
-                    }
-                })
-            })
-            .fold(([0, 0], f32::MAX), |(acc_pos, acc_d), (pos, d)| {
-                if acc_d <= d {
-                    (acc_pos, acc_d)
-                } else {
-                    (pos, d)
-                }
-            })
+        zone_dist_min(&self.zone_dist)
     }
 
     /// Finds the minimal distance of each column.
@@ -111,109 +618,257 @@ impl<const RES_X: usize, const RES_Y: usize> SensorMeasurement<RES_X, RES_Y> {
     }
 
     /// Attempts to recognize a hand from the measurement and finds its position, distance, etc. .
-    pub(crate) fn recognize_hand(&self, params: &SensorParams, threshold_dist: f32) -> HandState {
-        let hand_pos = self.hand_pos(params);
+    ///
+    /// Objects that are too large to be a hand (e.g. forearms or bodies walking past) are rejected.
+    /// The zones are weighted with the confidence of the optional zone quality.
+    /// The per-zone computations use the real number type of the zone geometry, only the results are converted.
+    pub(crate) fn recognize_hand<T: Real>(
+        &self,
+        params: &SensorParams,
+        geometry: &ZoneGeometry<T, RES_X, RES_Y>,
+        recognizer_params: &RecognizerParams,
+        threshold_dist: f32,
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+    ) -> HandState {
+        let Some([x, y, z]) = self.hand_pos(geometry, params, zone_quality) else {
+            return HandState::HandNotFound;
+        };
+        let r = x.hypot(y).hypot(z);
 
-        if hand_pos.r > 0.0 && hand_pos.r <= threshold_dist {
-            HandState::HandFound { hand_pos }
-        } else {
-            HandState::HandNotFound
+        if r <= T::ZERO || r > T::from_f32(threshold_dist) {
+            return HandState::HandNotFound;
         }
-    }
 
-    /// Attempts to find the hand position. Expects that there is at least one valid distance value in one of the zones,
-    /// else returns invalid spherical coordinates.
-    fn hand_pos(&self, params: &SensorParams) -> CoordsSpherical {
-        // First calculate the positions of the zone measurements
-        let mut zones_pos = [[CoordsSpherical::invalid(); RES_X]; RES_Y];
+        let hand_features = self.hand_features(geometry, recognizer_params);
 
-        for (pos_y, row) in self.zone_dist.iter().enumerate() {
-            for (pos_x, zone) in row.iter().enumerate() {
-                zones_pos[pos_y][pos_x] =
-                    dist_position_spher::<RES_X, RES_Y>(*zone, pos_x, pos_y, params);
-            }
+        if recognizer_params.hand_max_area > 0.0
+            && hand_features.area > recognizer_params.hand_max_area
+        {
+            return HandState::HandNotFound;
         }
 
-        let (min_dist_zone_pos, _min_dist) = self.min_dist();
-        let min_pos_spher = zones_pos[min_dist_zone_pos[1]][min_dist_zone_pos[0]];
+        let [x, y, z] = [x, y, z].map(T::to_f32);
+        HandState::HandFound {
+            hand_pos: CoordsCartesian { x, y, z }.into(),
+            hand_features,
+        }
+    }
 
-        // Then calculate the the hand position as weighted average mean,
-        // given the smallest distance as initial position and weighing in the distance of the other measurements to it.
+    /// Finds the zones that belong to the hand.
+    ///
+    /// These are the zones connected to the zone with minimal distance, that are within the depth range behind it.
+    fn hand_zones(&self, depth_range: f32) -> [[bool; RES_X]; RES_Y] {
+        let mut zones = [[false; RES_X]; RES_Y];
+        let ([min_x, min_y], min_dist) = self.min_dist();
 
-        // Calculates the weight of the positions in regards to min_pos for the weighted average mean
-        fn pos_weight(min_pos: &CoordsCartesian, other: &CoordsCartesian) -> f32 {
-            /// This is the factor that determines how much the distance weighs in into the average mean.
-            ///
-            /// E.g. a factor of 10.0 means that a position 1cm away from the measurement with min dist weighs in with
-            /// value 10.0, a position 2cm away weighs in with value 5.0.
-            const WEIGHT_FACTOR: f32 = 100.0;
+        if min_dist <= 0.0 || min_dist == f32::MAX {
+            return zones;
+        }
+        zones[min_y][min_x] = true;
 
-            let dist_to = min_pos.dist_to(other);
+        // Grow the region until no more connected zones are found
+        let mut grown = true;
+        while grown {
+            grown = false;
 
-            WEIGHT_FACTOR / dist_to
-        }
+            for pos_y in 0..RES_Y {
+                for pos_x in 0..RES_X {
+                    let dist = self.zone_dist[pos_y][pos_x];
 
-        // The sum of the positions multiplied by the weight (numerator of the weighted mean)
-        let coords_sum_weighted = (0..RES_Y)
-            .flat_map(|pos_y| {
-                (0..RES_X).filter_map(move |pos_x| {
-                    if zones_pos[pos_y][pos_x].is_invalid() {
-                        return None;
+                    if zones[pos_y][pos_x] || dist <= 0.0 || dist > min_dist + depth_range {
+                        continue;
                     }
-                    let c_cart = CoordsCartesian::from(zones_pos[pos_y][pos_x]);
-
-                    let weight = if pos_x == min_dist_zone_pos[0] && pos_y == min_dist_zone_pos[1] {
-                        // if this is the zone with min dist, skip the weight calc
-                        1.0
-                    } else {
-                        pos_weight(&min_pos_spher.into(), &c_cart)
-                    };
-
-                    Some(CoordsCartesian {
-                        x: c_cart.x * weight,
-                        y: c_cart.y * weight,
-                        z: c_cart.z * weight,
-                    })
-                })
-            })
-            .fold(CoordsCartesian::zero(), |mut acc, x| {
-                acc.x += x.x;
-                acc.y += x.y;
-                acc.z += x.z;
-                acc
-            });
+                    let connected = (pos_x > 0 && zones[pos_y][pos_x - 1])
+                        || (pos_x + 1 < RES_X && zones[pos_y][pos_x + 1])
+                        || (pos_y > 0 && zones[pos_y - 1][pos_x])
+                        || (pos_y + 1 < RES_Y && zones[pos_y + 1][pos_x]);
 
-        // The sum of the weights (denominator of the weighted mean)
-        let summed_weight: f32 = (0..RES_Y)
-            .flat_map(|pos_y| {
-                (0..RES_X).filter_map(move |pos_x| {
-                    if zones_pos[pos_y][pos_x].is_invalid() {
-                        return None;
+                    if connected {
+                        zones[pos_y][pos_x] = true;
+                        grown = true;
                     }
-                    let c_cart = CoordsCartesian::from(zones_pos[pos_y][pos_x]);
+                }
+            }
+        }
 
-                    let weight = if pos_x == min_dist_zone_pos[0] && pos_y == min_dist_zone_pos[1] {
-                        // if this is the zone with min dist, skip the weight calc
-                        1.0
-                    } else {
-                        pos_weight(&min_pos_spher.into(), &c_cart)
-                    };
+        zones
+    }
 
-                    Some(weight)
-                })
-            })
-            .sum();
+    /// Computes the size and shape features of the hand and classifies its pose.
+    ///
+    /// The zone positions and the sums are computed with the real number type of the zone geometry.
+    pub(crate) fn hand_features<T: Real>(
+        &self,
+        geometry: &ZoneGeometry<T, RES_X, RES_Y>,
+        recognizer_params: &RecognizerParams,
+    ) -> HandFeatures {
+        /// The zone footprint sizes are divided by this for the area sum, so it stays in range of fixed-point numbers.
+        const AREA_SCALE: i32 = 10;
+        /// The positions relative to their mean are divided by this for the moments, for the same reason.
+        const MOMENT_SCALE: i32 = 64;
 
-        if summed_weight == 0.0 {
-            return CoordsSpherical::invalid();
+        let zones = self.hand_zones(recognizer_params.hand_depth_range);
+        let hand_zones = || {
+            (0..RES_Y)
+                .flat_map(|pos_y| (0..RES_X).map(move |pos_x| (pos_x, pos_y)))
+                .filter(|&(pos_x, pos_y)| zones[pos_y][pos_x])
+        };
+        // The position projected on the plane facing the sensor (y-z-plane) and the footprint size of a zone
+        let zone = |pos_x: usize, pos_y: usize| {
+            let dist = T::from_f32(self.zone_dist[pos_y][pos_x]);
+            let [_, y, z] = geometry.position(dist, pos_x, pos_y);
+            let [size_hor, size_vert] = geometry.size(pos_x, pos_y);
+
+            ([y, z], [dist * size_hor, dist * size_vert])
+        };
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let max = |a: T, b: T| if b > a { b } else { a };
+        let two = T::from_i32(2);
+
+        let mut features = HandFeatures::empty();
+        let (mut x_min, mut x_max, mut y_min, mut y_max) = (usize::MAX, 0, usize::MAX, 0);
+        // The bounds of the zone footprints as (left, right, bottom, top)
+        let mut bounds = None;
+        let (mut area, mut sum_y, mut sum_z) = (T::ZERO, T::ZERO, T::ZERO);
+
+        for (pos_x, pos_y) in hand_zones() {
+            let ([y, z], [width, height]) = zone(pos_x, pos_y);
+            let area_scale = T::from_i32(AREA_SCALE);
+            let zone_bounds = (
+                y - width / two,
+                y + width / two,
+                z - height / two,
+                z + height / two,
+            );
+
+            // The footprint of the zone at its distance
+            area += (width / area_scale) * (height / area_scale);
+            features.zones += 1;
+            x_min = x_min.min(pos_x);
+            x_max = x_max.max(pos_x);
+            y_min = y_min.min(pos_y);
+            y_max = y_max.max(pos_y);
+            bounds = Some(match bounds {
+                Some((left, right, bottom, top)) => (
+                    min(left, zone_bounds.0),
+                    max(right, zone_bounds.1),
+                    min(bottom, zone_bounds.2),
+                    max(top, zone_bounds.3),
+                ),
+                None => zone_bounds,
+            });
+            sum_y += y;
+            sum_z += z;
         }
 
-        let mean = CoordsCartesian {
-            x: coords_sum_weighted.x / summed_weight,
-            y: coords_sum_weighted.y / summed_weight,
-            z: coords_sum_weighted.z / summed_weight,
+        let Some((left, right, bottom, top)) = bounds else {
+            return features;
         };
-        mean.into()
+
+        // The orientation and elongation from the second order central moments
+        let n = T::from_i32(features.zones as i32);
+        let (mean_y, mean_z) = (sum_y / n, sum_z / n);
+        let (mut mu20, mut mu02, mut mu11) = (T::ZERO, T::ZERO, T::ZERO);
+        for (pos_x, pos_y) in hand_zones() {
+            let ([y, z], _) = zone(pos_x, pos_y);
+            let moment_scale = T::from_i32(MOMENT_SCALE);
+            let (y, z) = ((y - mean_y) / moment_scale, (z - mean_z) / moment_scale);
+
+            mu20 += y * y;
+            mu02 += z * z;
+            mu11 += y * z;
+        }
+        let (mu20, mu02, mu11) = (mu20 / n, mu02 / n, mu11 / n);
+        let root = ((mu20 - mu02) / two).hypot(mu11);
+        let lambda_major = (mu20 + mu02) / two + root;
+        let lambda_minor = max((mu20 + mu02) / two - root, T::ZERO);
+
+        if lambda_major > T::ZERO {
+            features.orientation = ((two * mu11).atan2(mu20 - mu02) / two).to_f32();
+            features.elongation = (T::ONE - lambda_minor / lambda_major)
+                .to_f32()
+                .clamp(0.0, 1.0);
+        }
+
+        features.area = area.to_f32() * (AREA_SCALE * AREA_SCALE) as f32;
+        features.width = (right - left).to_f32();
+        features.height = (top - bottom).to_f32();
+        features.compactness =
+            (n / T::from_i32(((x_max - x_min + 1) * (y_max - y_min + 1)) as i32)).to_f32();
+        features.pose = classify_pose(&features, recognizer_params);
+
+        features
+    }
+
+    /// Attempts to find the hand position as `[x, y, z]`. Returns `None` if there is no valid distance value in any of
+    /// the zones.
+    ///
+    /// The zone positions and their weights are computed with the real number type of the zone geometry.
+    /// The weights are scaled with the confidence of the optional zone quality.
+    fn hand_pos<T: Real>(
+        &self,
+        geometry: &ZoneGeometry<T, RES_X, RES_Y>,
+        params: &SensorParams,
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+    ) -> Option<[T; 3]> {
+        /// This is the factor that determines how much the distance weighs in into the average mean.
+        ///
+        /// E.g. a factor of 10 means that a position 1cm away from the measurement with min dist weighs in with
+        /// value 10, a position 2cm away weighs in with value 5.
+        const WEIGHT_FACTOR: i32 = 100;
+
+        let ([min_x, min_y], min_dist) = self.min_dist();
+
+        if min_dist == f32::MAX {
+            return None;
+        }
+        let min_pos = geometry.position(T::from_f32(min_dist), min_x, min_y);
+        let weight_factor = T::from_i32(WEIGHT_FACTOR);
+
+        // Calculate the the hand position as weighted average mean,
+        // given the smallest distance as initial position and weighing in the distance of the other measurements to it.
+        // The offsets to the initial position are summed up instead of the positions, so the sums stay small.
+        let mut offset_sum = [T::ZERO; 3];
+        let mut summed_weight = T::ZERO;
+
+        for (pos_y, row) in self.zone_dist.iter().enumerate() {
+            for (pos_x, &dist) in row.iter().enumerate() {
+                if dist < 0.0 {
+                    continue;
+                }
+                let conf = match zone_quality {
+                    Some(zone_quality) => zone_quality[pos_y][pos_x].confidence_with::<T>(params),
+                    None => T::ONE,
+                };
+
+                if pos_x == min_x && pos_y == min_y {
+                    // if this is the zone with min dist, skip the weight calc
+                    summed_weight += conf;
+                    continue;
+                }
+                let pos = geometry.position(T::from_f32(dist), pos_x, pos_y);
+                let offset = [
+                    pos[0] - min_pos[0],
+                    pos[1] - min_pos[1],
+                    pos[2] - min_pos[2],
+                ];
+                // Positions closer than 1mm weigh in like positions 1mm away
+                let dist_to = offset[0].hypot(offset[1]).hypot(offset[2]);
+                let dist_to = if dist_to < T::ONE { T::ONE } else { dist_to };
+                let weight = weight_factor / dist_to * conf;
+
+                for (sum, offset) in offset_sum.iter_mut().zip(offset) {
+                    *sum += offset * weight;
+                }
+                summed_weight += weight;
+            }
+        }
+
+        if summed_weight <= T::ZERO {
+            return None;
+        }
+
+        Some([0, 1, 2].map(|i| min_pos[i] + offset_sum[i] / summed_weight))
 
         /*
         // Simply use the zone with min dist as pos
@@ -229,6 +884,207 @@ impl<const RES_X: usize, const RES_Y: usize> SensorMeasurement<RES_X, RES_Y> {
     }
 }
 
+/// A single target of a zone in a multi-target measurement.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ZoneTarget {
+    /// The measured distance of the target.
+    ///
+    /// Invalid targets are represented by value -1.0.
+    pub dist: f32,
+    /// The optional quality of the target. Set to [ZoneQuality::unknown] if not available.
+    pub quality: ZoneQuality,
+}
+
+impl ZoneTarget {
+    /// An invalid target (distance is set to `-1.0`).
+    pub fn invalid() -> Self {
+        Self {
+            dist: -1.0,
+            quality: ZoneQuality::unknown(),
+        }
+    }
+}
+
+/// Represents a sensor measurement with multiple targets per zone, e.g. a hand in front of a far wall.
+///
+/// Sensors like the ST VL53L5CX and VL53L7CX can report up to four targets per zone.
+/// The zones are expected in the same orientation as in [SensorMeasurement].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MultiTargetMeasurement<const RES_X: usize, const RES_Y: usize, const TARGETS: usize> {
+    /// The targets of each zone.
+    pub zone_targets: [[[ZoneTarget; TARGETS]; RES_X]; RES_Y],
+    /// The time of the measurement in milliseconds. Must be monotonically increasing.
+    pub time_ms: u32,
+}
+
+impl<const RES_X: usize, const RES_Y: usize, const TARGETS: usize>
+    MultiTargetMeasurement<RES_X, RES_Y, TARGETS>
+{
+    /// An invalid measurement. The time is set to zero, all targets are set to invalid.
+    pub fn invalid() -> Self {
+        Self {
+            zone_targets: [[[ZoneTarget::invalid(); TARGETS]; RES_X]; RES_Y],
+            time_ms: 0,
+        }
+    }
+
+    /// Selects the nearest valid target of every zone.
+    ///
+    /// Targets that are closer than the minimum target distance of the sensor parameters (e.g. reflections of a cover glass)
+    /// or not accepted by the quality thresholds are skipped, so that a further target of the zone is used instead.
+    /// This is where the second target is useful, e.g. a hand seen through a partly transparent or reflective cover.
+    ///
+    /// Targets behind the selected target are not used. They are either the background behind the hand,
+    /// or can not be told apart from it, because the background model holds a single distance per zone.
+    /// The order of the targets within a zone does not matter.
+    ///
+    /// Returns the tuple: ("measurement of the selected targets", "quality of the selected targets").
+    pub fn nearest_targets(
+        &self,
+        params: &SensorParams,
+    ) -> (
+        SensorMeasurement<RES_X, RES_Y>,
+        [[ZoneQuality; RES_X]; RES_Y],
+    ) {
+        let mut measurement = SensorMeasurement::invalid();
+        measurement.time_ms = self.time_ms;
+        let mut zone_quality = [[ZoneQuality::unknown(); RES_X]; RES_Y];
+
+        for (pos_y, row) in self.zone_targets.iter().enumerate() {
+            for (pos_x, targets) in row.iter().enumerate() {
+                let nearest = targets
+                    .iter()
+                    .filter(|t| {
+                        t.dist > 0.0
+                            && t.dist >= params.min_target_dist
+                            && t.quality.is_accepted(params)
+                    })
+                    .min_by(|a, b| a.dist.total_cmp(&b.dist));
+
+                if let Some(target) = nearest {
+                    measurement.zone_dist[pos_y][pos_x] = target.dist;
+                    zone_quality[pos_y][pos_x] = target.quality;
+                }
+            }
+        }
+
+        (measurement, zone_quality)
+    }
+}
+
+/// Classifies the pose of the hand from its size and shape.
+///
+/// A finger is small and thin, a fist is compact and not much wider than a fist,
+/// an open palm is larger or wider than a fist. Other hands, e.g. small irregular shapes, are not classified.
+fn classify_pose(features: &HandFeatures, recognizer_params: &RecognizerParams) -> HandPose {
+    let finger_width = libm::sqrtf(recognizer_params.hand_finger_max_area);
+    let fist_width = libm::sqrtf(recognizer_params.hand_fist_max_area);
+    let min_extent = features.width.min(features.height);
+    let max_extent = features.width.max(features.height);
+
+    if features.area <= recognizer_params.hand_finger_max_area && min_extent <= finger_width {
+        HandPose::HandPoseFinger
+    } else if features.area <= recognizer_params.hand_fist_max_area
+        && max_extent <= 1.5 * fist_width
+        && features.compactness >= 0.5
+    {
+        HandPose::HandPoseFist
+    } else if features.area > recognizer_params.hand_fist_max_area || max_extent > 1.5 * fist_width
+    {
+        HandPose::HandPoseOpenPalm
+    } else {
+        HandPose::HandPoseUnknown
+    }
+}
+
+/// Rotates and mirrors the zones according to the mounting orientation.
+///
+/// Returns `None` if a rotation by 90 or 270 degrees does not preserve the resolution.
+pub(crate) fn zones_oriented<T, const RES_X: usize, const RES_Y: usize>(
+    zones: [[T; RES_X]; RES_Y],
+    params: &SensorParams,
+) -> Option<[[T; RES_X]; RES_Y]>
+where
+    T: Copy + Default,
+{
+    // The matrix rotations are clockwise with the first row at the top,
+    // but the first row of the zones is the bottom one, which reverses their direction.
+    let zones = match params.mounting_rotation {
+        MountingRotation::MountingRotation0 => zones,
+        MountingRotation::MountingRotation90 => {
+            zones_same_res(math::matrix_2d_rotate_270deg(zones))?
+        }
+        MountingRotation::MountingRotation180 => math::matrix_2d_rotate_180deg(zones),
+        MountingRotation::MountingRotation270 => {
+            zones_same_res(math::matrix_2d_rotate_90deg(zones))?
+        }
+    };
+    let zones = if params.mounting_mirror_horizontal {
+        math::matrix_2d_reverse_rows(zones)
+    } else {
+        zones
+    };
+    let zones = if params.mounting_mirror_vertical {
+        math::matrix_2d_reverse_cols(zones)
+    } else {
+        zones
+    };
+
+    Some(zones)
+}
+
+/// Converts the transposed zones back to the original resolution, which only succeeds for square resolutions.
+fn zones_same_res<T, const RES_X: usize, const RES_Y: usize>(
+    transposed: [[T; RES_Y]; RES_X],
+) -> Option<[[T; RES_X]; RES_Y]>
+where
+    T: Copy + Default,
+{
+    if RES_X != RES_Y {
+        return None;
+    }
+    let mut zones = [[T::default(); RES_X]; RES_Y];
+
+    for (row, transposed_row) in zones.iter_mut().zip(transposed.iter()) {
+        for (zone, &transposed_zone) in row.iter_mut().zip(transposed_row.iter()) {
+            *zone = transposed_zone;
+        }
+    }
+
+    Some(zones)
+}
+
+/// Finds the position in the matrix and distance value of the zone with minimal distance.
+///
+/// Returns the tuple: (["x-pos in matrix", "y-pos in matrix"], "distance").
+pub(crate) fn zone_dist_min<const RES_X: usize, const RES_Y: usize>(
+    zone_dist: &[[f32; RES_X]; RES_Y],
+) -> ([usize; 2], f32) {
+    zone_dist
+        .iter()
+        .enumerate()
+        .flat_map(|(y_pos, r)| {
+            r.iter().enumerate().filter_map(move |(x_pos, &d)| {
+                if d > 0.0 {
+                    Some(([x_pos, y_pos], d))
+                } else {
+                    None
+                }
+            })
+        })
+        .fold(([0, 0], f32::MAX), |(acc_pos, acc_d), (pos, d)| {
+            if acc_d <= d {
+                (acc_pos, acc_d)
+            } else {
+                (pos, d)
+            }
+        })
+}
+
 /// Calculates the position in space for the measurement in a sensor zone.
 ///
 /// Arguments:
@@ -236,6 +1092,8 @@ impl<const RES_X: usize, const RES_Y: usize> SensorMeasurement<RES_X, RES_Y> {
 /// - zone_pos_x: The x-index of the zone in the sensor grid
 /// - zone_pos_y: The y-index of the zone in the sensor grid
 ///
+/// The direction of the zone center is given by the optical model of the sensor parameters.
+///
 /// Returns the position in spherical coordinates.
 pub(crate) fn dist_position_spher<const RES_X: usize, const RES_Y: usize>(
     dist: f32,
@@ -243,23 +1101,70 @@ pub(crate) fn dist_position_spher<const RES_X: usize, const RES_Y: usize>(
     zone_pos_y: usize,
     params: &SensorParams,
 ) -> CoordsSpherical {
-    let angle_per_zone_hor = (params.fov_horizontal / (RES_X as f32)).to_radians();
-    let angle_per_zone_vert = (params.fov_vertical / (RES_Y as f32)).to_radians();
-
     let r = dist;
 
     if r < 0.0 {
         return CoordsSpherical::invalid();
     }
 
-    let theta = (zone_pos_x as f32 - (RES_X as f32 / 2.0)) * angle_per_zone_hor;
+    let (angle_hor, angle_vert) = params.zone_angles::<RES_X, RES_Y>(zone_pos_x, zone_pos_y);
+    let theta = angle_hor;
     // 90.0 deg - .. because the z-axis is pointing up from the center of the sensor grid
-    let phi = FRAC_PI_2 - (zone_pos_y as f32 - (RES_Y as f32 / 2.0)) * angle_per_zone_vert;
+    let phi = FRAC_PI_2 - angle_vert;
 
     CoordsSpherical { r, theta, phi }
 }
 
-/// Finds the nearest zone for all given measurements.
+/// The directions and sizes of the zones, precomputed from the sensor parameters.
+///
+/// Computed once with floats, so the per-zone computations need no trigonometric functions of the real number type.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ZoneGeometry<T, const RES_X: usize, const RES_Y: usize> {
+    /// The unit vector `[x, y, z]` pointing to the center of each zone.
+    direction: [[[T; 3]; RES_X]; RES_Y],
+    /// The angular size of each zone as `[horizontal, vertical]` angles (rad), see [SensorParams::zone_size_angles].
+    size: [[[T; 2]; RES_X]; RES_Y],
+}
+
+impl<T: Real, const RES_X: usize, const RES_Y: usize> ZoneGeometry<T, RES_X, RES_Y> {
+    pub(crate) fn new(params: &SensorParams) -> Self {
+        let mut geometry = Self {
+            direction: [[[T::ZERO; 3]; RES_X]; RES_Y],
+            size: [[[T::ZERO; 2]; RES_X]; RES_Y],
+        };
+
+        for pos_y in 0..RES_Y {
+            for pos_x in 0..RES_X {
+                let (angle_hor, angle_vert) = params.zone_angles::<RES_X, RES_Y>(pos_x, pos_y);
+                let (size_hor, size_vert) = params.zone_size_angles::<RES_X, RES_Y>(pos_x, pos_y);
+                let dir_hor = libm::cosf(angle_vert);
+
+                geometry.direction[pos_y][pos_x] = [
+                    dir_hor * libm::cosf(angle_hor),
+                    dir_hor * libm::sinf(angle_hor),
+                    libm::sinf(angle_vert),
+                ]
+                .map(T::from_f32);
+                geometry.size[pos_y][pos_x] = [size_hor, size_vert].map(T::from_f32);
+            }
+        }
+
+        geometry
+    }
+
+    /// The position in space `[x, y, z]` of a distance measured in a zone, like [dist_position_spher].
+    pub(crate) fn position(&self, dist: T, zone_pos_x: usize, zone_pos_y: usize) -> [T; 3] {
+        self.direction[zone_pos_y][zone_pos_x].map(|direction| dist * direction)
+    }
+
+    /// The angular size of a zone as `[horizontal, vertical]` angles (rad).
+    pub(crate) fn size(&self, zone_pos_x: usize, zone_pos_y: usize) -> [T; 2] {
+        self.size[zone_pos_y][zone_pos_x]
+    }
+}
+
+/// Finds the nearest zone for all given zone distances of measurements.
 ///
 /// Returns the tuple:
 ///
@@ -267,15 +1172,15 @@ pub(crate) fn dist_position_spher<const RES_X: usize, const RES_Y: usize>(
 pub(crate) fn find_nearest_zone<
     const RES_X: usize,
     const RES_Y: usize,
-    T: IntoIterator<Item = SensorMeasurement<RES_X, RES_Y>>,
+    T: IntoIterator<Item = [[f32; RES_X]; RES_Y]>,
 >(
-    measurements: T,
+    zone_dists: T,
 ) -> (usize, [usize; 2], f32) {
-    measurements
+    zone_dists
         .into_iter()
         .enumerate()
         .fold((0, [0, 0], f32::MAX), |acc, m| {
-            let m_min_dist = m.1.min_dist();
+            let m_min_dist = zone_dist_min(&m.1);
 
             if acc.2 <= m_min_dist.1 {
                 acc
@@ -300,7 +1205,7 @@ pub(crate) fn find_nearest_hand_pos<T: IntoIterator<Item = HandState>>(
         .into_iter()
         .enumerate()
         .filter_map(|(i, hs)| {
-            if let HandState::HandFound { hand_pos } = hs {
+            if let HandState::HandFound { hand_pos, .. } = hs {
                 Some((i, hand_pos))
             } else {
                 None
@@ -317,3 +1222,440 @@ pub(crate) fn find_nearest_hand_pos<T: IntoIterator<Item = HandState>>(
             },
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        HandPose, HandState, MountingRotation, MultiTargetMeasurement, OpticalModel,
+        SensorMeasurement, SensorParams, ZoneGeometry, ZoneQuality, ZoneTarget,
+    };
+    use crate::{Fixed, RecognizerParams};
+    use approx::{assert_abs_diff_eq, assert_relative_eq};
+    use core::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn measurement_oriented() {
+        let mut params = SensorParams::default_vl53l5cx();
+        let measurement = SensorMeasurement::new([[1.0, 2.0], [3.0, 4.0]]);
+
+        // The first row is the bottom one, so the bottom right zone 2.0 is rotated clockwise to the bottom left
+        params.mounting_rotation = MountingRotation::MountingRotation90;
+        assert_eq!(
+            measurement.oriented(&params).unwrap().zone_dist,
+            [[2.0, 4.0], [1.0, 3.0]]
+        );
+        params.mounting_rotation = MountingRotation::MountingRotation270;
+        assert_eq!(
+            measurement.oriented(&params).unwrap().zone_dist,
+            [[3.0, 1.0], [4.0, 2.0]]
+        );
+
+        params.mounting_rotation = MountingRotation::MountingRotation90;
+        params.mounting_mirror_horizontal = true;
+        assert_eq!(
+            measurement.oriented(&params).unwrap().zone_dist,
+            [[4.0, 2.0], [3.0, 1.0]]
+        );
+
+        params.mounting_mirror_vertical = true;
+        assert_eq!(
+            measurement.oriented(&params).unwrap().zone_dist,
+            [[3.0, 1.0], [4.0, 2.0]]
+        );
+
+        // Rotating by 90 degrees does not preserve non-square resolutions
+        assert!(SensorMeasurement::new([[1.0, 2.0]])
+            .oriented(&params)
+            .is_none());
+        params.mounting_rotation = MountingRotation::MountingRotation180;
+        assert!(SensorMeasurement::new([[1.0, 2.0]])
+            .oriented(&params)
+            .is_some());
+    }
+
+    #[test]
+    fn zone_quality_confidence() {
+        let params = SensorParams::default_vl53l5cx();
+        let quality = |target_status, signal_per_spad, range_sigma| ZoneQuality {
+            target_status,
+            signal_per_spad,
+            range_sigma,
+        };
+
+        assert_relative_eq!(ZoneQuality::unknown().confidence(&params), 1.0);
+        assert_relative_eq!(quality(5, 10.0, 0.0).confidence(&params), 1.0);
+        // Half of the maximum range sigma
+        assert_relative_eq!(quality(5, 10.0, 15.0).confidence(&params), 0.8);
+        assert_relative_eq!(quality(9, -1.0, 30.0).confidence(&params), 0.5);
+        // Rejected by the target status or the range sigma
+        assert_relative_eq!(quality(4, 10.0, 1.0).confidence(&params), 0.0);
+        assert_relative_eq!(quality(40, 10.0, 1.0).confidence(&params), 0.0);
+        assert_relative_eq!(quality(5, 10.0, 31.0).confidence(&params), 0.0);
+
+        // The maximum range sigma rounds to zero as a fixed-point number, which must not be divided by
+        let tiny_sigma_params = SensorParams {
+            quality_max_range_sigma: 1e-5,
+            ..params
+        };
+        assert_relative_eq!(
+            quality(5, 10.0, 5e-6)
+                .confidence_with::<Fixed>(&tiny_sigma_params)
+                .to_f32(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn measurement_reject_low_quality() {
+        let mut params = SensorParams::default_vl53l5cx();
+        params.quality_min_signal_per_spad = 2.0;
+        let measurement = SensorMeasurement::new([[100.0, 200.0], [300.0, 400.0]]);
+        let mut zone_quality = [[ZoneQuality::unknown(); 2]; 2];
+        zone_quality[0][1].target_status = 4;
+        zone_quality[1][0].signal_per_spad = 1.0;
+        zone_quality[1][1].signal_per_spad = 3.0;
+
+        assert_eq!(
+            measurement
+                .reject_low_quality(&zone_quality, &params)
+                .zone_dist,
+            [[100.0, -1.0], [-1.0, 400.0]]
+        );
+    }
+
+    #[test]
+    fn hand_pos_weighted_by_confidence() {
+        let params = SensorParams::default_vl53l5cx();
+        let mut measurement = SensorMeasurement::<4, 4>::invalid();
+        measurement.zone_dist[1][1] = 200.0;
+        measurement.zone_dist[1][2] = 210.0;
+        let mut zone_quality = [[ZoneQuality::unknown(); 4]; 4];
+        zone_quality[1][2].range_sigma = 90.0;
+
+        let geometry = ZoneGeometry::<f32, 4, 4>::new(&params);
+
+        let [_, unweighted_y, _] = measurement.hand_pos(&geometry, &params, None).unwrap();
+        let [_, weighted_y, _] = measurement
+            .hand_pos(&geometry, &params, Some(&zone_quality))
+            .unwrap();
+        // The uncertain zone pulls the hand position less to the right
+        assert!(weighted_y < unweighted_y);
+        assert!(SensorMeasurement::<4, 4>::invalid()
+            .hand_pos(&geometry, &params, None)
+            .is_none());
+    }
+
+    #[test]
+    fn hand_zones_region_growing() {
+        let measurement = SensorMeasurement::new([
+            [150.0, 160.0, -1.0, 170.0],
+            [100.0, 400.0, -1.0, -1.0],
+            [120.0, 130.0, 175.0, -1.0],
+            [-1.0, -1.0, 200.0, 110.0],
+        ]);
+
+        // Connected to the nearest zone and within the depth range, not through invalid or further zones
+        assert_eq!(
+            measurement.hand_zones(80.0),
+            [
+                [true, true, false, false],
+                [true, false, false, false],
+                [true, true, true, false],
+                [false, false, false, false],
+            ]
+        );
+        assert_eq!(
+            SensorMeasurement::<2, 1>::invalid().hand_zones(80.0),
+            [[false; 2]]
+        );
+    }
+
+    #[test]
+    fn hand_pose_classification() {
+        let geometry = ZoneGeometry::<f32, 8, 8>::new(&SensorParams::default_vl53l5cx());
+        let recognizer_params = RecognizerParams::default();
+        // A hand covering the zones of the given columns and rows at the given distance
+        let pose = |columns: core::ops::Range<usize>, rows: core::ops::Range<usize>, dist| {
+            let mut measurement = SensorMeasurement::<8, 8>::invalid();
+            for row in measurement.zone_dist[rows].iter_mut() {
+                row[columns.clone()].fill(dist);
+            }
+
+            measurement
+                .hand_features(&geometry, &recognizer_params)
+                .pose
+        };
+
+        assert_eq!(pose(3..4, 3..5, 300.0), HandPose::HandPoseFinger);
+        assert_eq!(pose(3..6, 3..6, 300.0), HandPose::HandPoseFist);
+        assert_eq!(pose(2..6, 1..7, 300.0), HandPose::HandPoseOpenPalm);
+        // A wide stripe is not a fist, even with a small area
+        assert_eq!(pose(1..7, 3..4, 250.0), HandPose::HandPoseOpenPalm);
+        // A flat fist far away, that is too wide for a finger
+        assert_eq!(pose(3..4, 3..4, 800.0), HandPose::HandPoseFist);
+
+        // A diagonal staircase is neither
+        let mut measurement = SensorMeasurement::<8, 8>::invalid();
+        for (y, x) in [(3, 3), (3, 4), (4, 4), (4, 5), (5, 5), (5, 6), (6, 6)] {
+            measurement.zone_dist[y][x] = 300.0;
+        }
+        assert_eq!(
+            measurement
+                .hand_features(&geometry, &recognizer_params)
+                .pose,
+            HandPose::HandPoseUnknown
+        );
+    }
+
+    #[test]
+    fn hand_orientation_moments() {
+        let geometry = ZoneGeometry::<f32, 8, 8>::new(&SensorParams::default_vl53l5cx());
+        let features = |is_hand: fn(usize, usize) -> bool| {
+            let mut measurement = SensorMeasurement::<8, 8>::invalid();
+            for (y, row) in measurement.zone_dist.iter_mut().enumerate() {
+                for (x, dist) in row.iter_mut().enumerate() {
+                    if is_hand(x, y) {
+                        *dist = 300.0;
+                    }
+                }
+            }
+
+            measurement.hand_features(&geometry, &RecognizerParams::default())
+        };
+
+        // A horizontal and a vertical line
+        let horizontal = features(|_, y| y == 3);
+        assert_relative_eq!(horizontal.orientation, 0.0, epsilon = 1e-4);
+        assert_relative_eq!(horizontal.elongation, 1.0, epsilon = 1e-4);
+        let vertical = features(|x, _| x == 4);
+        assert_relative_eq!(vertical.orientation.abs(), FRAC_PI_2, epsilon = 1e-4);
+        assert_relative_eq!(vertical.elongation, 1.0, epsilon = 1e-4);
+        // Connected diagonals, rising to the right and falling to the right
+        let rising = features(|x, y| x == y || x == y + 1);
+        assert_relative_eq!(rising.orientation, FRAC_PI_4, epsilon = 0.05);
+        let falling = features(|x, y| x + y == 7 || x + y == 8);
+        assert_relative_eq!(falling.orientation, -FRAC_PI_4, epsilon = 0.05);
+        // A square has no principal axis
+        let square = features(|x, y| (2..6).contains(&x) && (2..6).contains(&y));
+        assert!(square.elongation < 0.05);
+    }
+
+    #[test]
+    fn hand_area_rejection() {
+        let params = SensorParams::default_vl53l5cx();
+        let geometry = ZoneGeometry::<f32, 8, 8>::new(&params);
+        // A body walking past, covering the whole FOV
+        let measurement = SensorMeasurement::new([[300.0; 8]; 8]);
+
+        let HandState::HandFound { hand_features, .. } = measurement.recognize_hand(
+            &params,
+            &geometry,
+            &RecognizerParams::default(),
+            400.0,
+            None,
+        ) else {
+            panic!("the rejection should be disabled by default");
+        };
+        assert_eq!(hand_features.zones, 64);
+        // The footprints of the zones cover the FOV
+        assert_relative_eq!(
+            hand_features.width,
+            2.0 * 300.0 * 22.5_f32.to_radians(),
+            max_relative = 0.05
+        );
+
+        let recognizer_params = RecognizerParams {
+            hand_max_area: 40000.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            measurement.recognize_hand(&params, &geometry, &recognizer_params, 400.0, None),
+            HandState::HandNotFound
+        ));
+    }
+
+    #[test]
+    fn multi_target_nearest() {
+        let mut params = SensorParams::default_vl53l5cx();
+        params.min_target_dist = 50.0;
+        let target = |dist, target_status| ZoneTarget {
+            dist,
+            quality: ZoneQuality {
+                target_status,
+                ..ZoneQuality::unknown()
+            },
+        };
+        let mut measurement = MultiTargetMeasurement::<4, 1, 2>::invalid();
+        measurement.time_ms = 10;
+        // Ordered by distance, and in reverse order
+        measurement.zone_targets[0][0] = [target(200.0, 5), target(900.0, 5)];
+        measurement.zone_targets[0][1] = [target(900.0, 5), target(200.0, 9)];
+        // Reflection of a cover glass, the hand behind it is the second target
+        measurement.zone_targets[0][2] = [target(20.0, 5), target(250.0, 5)];
+        // Low quality nearest target, and no valid target at all
+        measurement.zone_targets[0][3] = [target(150.0, 4), target(-1.0, 5)];
+
+        let (nearest, zone_quality) = measurement.nearest_targets(&params);
+        assert_eq!(nearest.zone_dist, [[200.0, 200.0, 250.0, -1.0]]);
+        assert_eq!(nearest.time_ms, 10);
+        assert_eq!(zone_quality[0][1].target_status, 9);
+        assert_eq!(zone_quality[0][3], ZoneQuality::unknown());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measurement_from_slices() {
+        let measurement =
+            SensorMeasurement::<2, 2>::from_rows(vec![vec![1.0, 2.0], vec![3.0, 4.0]], 10).unwrap();
+        assert_eq!(measurement.zone_dist, [[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(measurement.time_ms, 10);
+
+        assert!(SensorMeasurement::<2, 2>::from_slice(&[1.0, 2.0, 3.0], 10).is_none());
+        assert!(SensorMeasurement::<2, 2>::from_rows(vec![vec![1.0, 2.0, 3.0, 4.0]], 10).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn measurement_serde_roundtrip() {
+        let mut measurement = SensorMeasurement::<3, 2>::new([[1.0, 2.0, 3.0], [4.0, 5.0, -1.0]]);
+        measurement.time_ms = 10;
+
+        let json = serde_json_core::to_string::<_, 512>(&measurement).unwrap();
+        assert!(json.starts_with(r#"{"zone_dist":[[1.0,2.0,3.0],[4.0,5.0,-1.0]]"#));
+        let (deserialized, _) =
+            serde_json_core::from_str::<SensorMeasurement<3, 2>>(&json).unwrap();
+        assert_eq!(deserialized.zone_dist, measurement.zone_dist);
+        assert_eq!(deserialized.time_ms, 10);
+
+        // The resolution must match
+        assert!(serde_json_core::from_str::<SensorMeasurement<2, 2>>(&json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn measurement_postcard_roundtrip() {
+        let mut measurement = SensorMeasurement::<3, 2>::new([[1.0, 2.0, 3.0], [4.0, 5.0, -1.0]]);
+        measurement.time_ms = 10;
+
+        // The zones are stored without lengths, like arrays, followed by the time as a varint
+        let mut buf = [0; 64];
+        let bytes = postcard::to_slice(&measurement, &mut buf).unwrap();
+        assert_eq!(bytes.len(), 6 * 4 + 1);
+        let deserialized = postcard::from_bytes::<SensorMeasurement<3, 2>>(bytes).unwrap();
+        assert_eq!(deserialized.zone_dist, measurement.zone_dist);
+        assert_eq!(deserialized.time_ms, 10);
+    }
+
+    #[test]
+    fn zone_angles_centered() {
+        let mut params = SensorParams::default_vl53l5cx();
+
+        // The zone centers are symmetric around the sensor center, the first zone is the bottom left one
+        let (left, bottom) = params.zone_angles::<4, 4>(0, 0);
+        let (right, top) = params.zone_angles::<4, 4>(3, 3);
+        assert!(left < 0.0 && bottom < 0.0);
+        assert_relative_eq!(left, -right);
+        assert_relative_eq!(bottom, -top);
+        assert_relative_eq!(right, 16.875_f32.to_radians());
+
+        params.optical_model = OpticalModel::OpticalModelPinhole;
+        let (right, _) = params.zone_angles::<4, 4>(3, 0);
+        assert_relative_eq!(right, libm::atanf(0.75 * 22.5_f32.to_radians().tan()));
+
+        params.optical_model = OpticalModel::OpticalModelLookupTable;
+        params.optical_lut_angle_hor[0][3] = 18.0;
+        params.optical_lut_angle_vert[0][3] = -18.0;
+        let (right, bottom) = params.zone_angles::<4, 4>(3, 0);
+        assert_relative_eq!(right, 18.0_f32.to_radians());
+        assert_relative_eq!(bottom, -18.0_f32.to_radians());
+        // The lowest zone is below the sensor
+        let [_, _, z] = ZoneGeometry::<f32, 4, 4>::new(&params).position(100.0, 3, 0);
+        assert!(z < 0.0);
+    }
+
+    #[test]
+    fn hand_fixed_point_equivalent() {
+        let recognizer_params = RecognizerParams::default();
+
+        for optical_model in [
+            OpticalModel::OpticalModelLinear,
+            OpticalModel::OpticalModelPinhole,
+        ] {
+            let mut params = SensorParams::default_vl53l5cx();
+            params.optical_model = optical_model;
+            let geometry_float = ZoneGeometry::<f32, 8, 8>::new(&params);
+            let geometry_fixed = ZoneGeometry::<Fixed, 8, 8>::new(&params);
+
+            // A tilted hand with fingers, moving across the FOV and away from the sensor
+            for step in 0..12_usize {
+                let mut measurement = SensorMeasurement::<8, 8>::invalid();
+                for (pos_y, row) in measurement.zone_dist.iter_mut().enumerate() {
+                    for (pos_x, dist) in row.iter_mut().enumerate() {
+                        let column = pos_x as i32 - step as i32 / 2 - pos_y as i32 / 3;
+                        if (0..3).contains(&column) && pos_y > 1 {
+                            *dist = 80.0 + step as f32 * 60.0 + (pos_x * 7 + pos_y * 13) as f32;
+                        } else if pos_y == 0 {
+                            *dist = 1500.0;
+                        }
+                    }
+                }
+
+                let pos_float = measurement
+                    .hand_pos(&geometry_float, &params, None)
+                    .unwrap();
+                let pos_fixed = measurement
+                    .hand_pos(&geometry_fixed, &params, None)
+                    .unwrap();
+                for (float, fixed) in pos_float.into_iter().zip(pos_fixed) {
+                    assert_abs_diff_eq!(float, fixed.to_f32(), epsilon = 0.5);
+                }
+
+                let features_float = measurement.hand_features(&geometry_float, &recognizer_params);
+                let features_fixed = measurement.hand_features(&geometry_fixed, &recognizer_params);
+                assert_eq!(features_float.zones, features_fixed.zones);
+                assert_eq!(features_float.pose, features_fixed.pose);
+                assert_relative_eq!(
+                    features_float.area,
+                    features_fixed.area,
+                    max_relative = 0.01
+                );
+                assert_abs_diff_eq!(features_float.width, features_fixed.width, epsilon = 0.5);
+                assert_abs_diff_eq!(
+                    features_float.orientation,
+                    features_fixed.orientation,
+                    epsilon = 0.01
+                );
+                assert_abs_diff_eq!(
+                    features_float.elongation,
+                    features_fixed.elongation,
+                    epsilon = 0.01
+                );
+            }
+        }
+
+        // The sums stay in range of fixed-point numbers for a body covering the whole FOV at the end of the range
+        let params = SensorParams::default_tmf882x();
+        let measurement = SensorMeasurement::<8, 8>::new([[4900.0; 8]; 8]);
+        let features_float =
+            measurement.hand_features(&ZoneGeometry::<f32, 8, 8>::new(&params), &recognizer_params);
+        let features_fixed = measurement.hand_features(
+            &ZoneGeometry::<Fixed, 8, 8>::new(&params),
+            &recognizer_params,
+        );
+        assert_relative_eq!(
+            features_float.area,
+            features_fixed.area,
+            max_relative = 0.01
+        );
+        assert_relative_eq!(
+            features_float.width,
+            features_fixed.width,
+            max_relative = 0.01
+        );
+        assert_abs_diff_eq!(
+            features_float.elongation,
+            features_fixed.elongation,
+            epsilon = 0.01
+        );
+    }
+}
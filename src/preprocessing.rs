@@ -0,0 +1,404 @@
+//! Measurement Preprocessing.
+//!
+//! Stages that are applied to the measurements before the hand is recognized.
+
+use crate::SensorMeasurement;
+
+/// The temporal filter that is applied to every zone of the measurements.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ZoneFilterMode {
+    /// The zones are not filtered.
+    ZoneFilterNone = 0,
+    /// The median of the last three measurements of a zone.
+    ///
+    /// A zone is only valid when at least two of the last three measurements are valid.
+    ZoneFilterMedian3,
+    /// Hysteresis on the valid / invalid transitions of a zone.
+    ///
+    /// A zone only changes between valid and invalid after the configured number of consecutive frames.
+    /// While an invalid measurement is suppressed, the last valid distance is held.
+    ZoneFilterHysteresis,
+}
+
+/// Filters every zone of the measurements over time, suppressing zones that flicker between valid and invalid.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZoneFilter<const RES_X: usize, const RES_Y: usize> {
+    mode: ZoneFilterMode,
+    hysteresis_frames: u32,
+    /// The last two unfiltered distances of every zone, the most recent first.
+    prev: [[[f32; RES_X]; RES_Y]; 2],
+    /// The current output of the hysteresis.
+    held: [[f32; RES_X]; RES_Y],
+    /// The number of consecutive frames contradicting the current validity of the hysteresis.
+    counter: [[u32; RES_X]; RES_Y],
+}
+
+impl<const RES_X: usize, const RES_Y: usize> ZoneFilter<RES_X, RES_Y> {
+    /// A new zone filter.
+    ///
+    /// `hysteresis_frames` is only used for [ZoneFilterMode::ZoneFilterHysteresis].
+    pub fn new(mode: ZoneFilterMode, hysteresis_frames: u32) -> Self {
+        Self {
+            mode,
+            hysteresis_frames,
+            prev: [[[-1.0; RES_X]; RES_Y]; 2],
+            held: [[-1.0; RES_X]; RES_Y],
+            counter: [[0; RES_X]; RES_Y],
+        }
+    }
+
+    /// Resets the filter, all zones are then considered invalid.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.mode, self.hysteresis_frames);
+    }
+
+    /// Filters the zones of the next measurement.
+    pub fn filter(
+        &mut self,
+        mut measurement: SensorMeasurement<RES_X, RES_Y>,
+    ) -> SensorMeasurement<RES_X, RES_Y> {
+        match self.mode {
+            ZoneFilterMode::ZoneFilterNone => {}
+            ZoneFilterMode::ZoneFilterMedian3 => {
+                let current = measurement.zone_dist;
+
+                for (y, row) in measurement.zone_dist.iter_mut().enumerate() {
+                    for (x, dist) in row.iter_mut().enumerate() {
+                        *dist = median3_valid([*dist, self.prev[0][y][x], self.prev[1][y][x]]);
+                    }
+                }
+
+                self.prev = [current, self.prev[0]];
+            }
+            ZoneFilterMode::ZoneFilterHysteresis => {
+                for (y, row) in measurement.zone_dist.iter_mut().enumerate() {
+                    for (x, dist) in row.iter_mut().enumerate() {
+                        let held = &mut self.held[y][x];
+                        let counter = &mut self.counter[y][x];
+
+                        if (*dist > 0.0) == (*held > 0.0) {
+                            // Agrees with the current validity
+                            *counter = 0;
+                            *held = *dist;
+                        } else {
+                            *counter += 1;
+
+                            if *counter >= self.hysteresis_frames {
+                                *counter = 0;
+                                *held = *dist;
+                            }
+                        }
+
+                        *dist = *held;
+                    }
+                }
+            }
+        }
+
+        measurement
+    }
+}
+
+/// A model of the static scene in front of the sensor, e.g. a ceiling, a table surface or a nearby wall.
+///
+/// Can be exported and stored, and later be restored.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundModel<const RES_X: usize, const RES_Y: usize> {
+    /// The background distance of each zone.
+    ///
+    /// Zones without background in range are represented by value -1.0.
+    pub zone_dist: [[f32; RES_X]; RES_Y],
+    /// The number of valid distances the background distance of each zone was learned from.
+    pub zone_frames: [[u16; RES_X]; RES_Y],
+    /// The number of frames the model was learned from. The model is only used when it was learned from at least one frame.
+    pub frames: u32,
+    /// The distance of a static object in each zone without background, that is not learned yet.
+    ///
+    /// Zones without such an object are represented by value -1.0.
+    pub candidate_dist: [[f32; RES_X]; RES_Y],
+    /// The number of consecutive frames the static object of each zone without background persisted.
+    pub candidate_frames: [[u16; RES_X]; RES_Y],
+}
+
+impl<const RES_X: usize, const RES_Y: usize> BackgroundModel<RES_X, RES_Y> {
+    /// The furthest background distance. Background that adapts beyond it is considered out of range.
+    pub const MAX_DIST: f32 = 4000.0;
+
+    /// An empty model, that was not learned from any frames.
+    pub fn empty() -> Self {
+        Self {
+            zone_dist: [[-1.0; RES_X]; RES_Y],
+            zone_frames: [[0; RES_X]; RES_Y],
+            frames: 0,
+            candidate_dist: [[-1.0; RES_X]; RES_Y],
+            candidate_frames: [[0; RES_X]; RES_Y],
+        }
+    }
+
+    /// Whether the model was learned from at least one frame.
+    pub fn is_calibrated(&self) -> bool {
+        self.frames > 0
+    }
+
+    /// Learns the background from a measurement with no hand present.
+    ///
+    /// The background distance of a zone is the mean of its valid distances.
+    pub fn learn(&mut self, measurement: &SensorMeasurement<RES_X, RES_Y>) {
+        self.frames += 1;
+
+        for ((bg_row, frames_row), row) in self
+            .zone_dist
+            .iter_mut()
+            .zip(self.zone_frames.iter_mut())
+            .zip(measurement.zone_dist.iter())
+        {
+            for ((bg, frames), &dist) in bg_row.iter_mut().zip(frames_row.iter_mut()).zip(row) {
+                if dist <= 0.0 {
+                    continue;
+                }
+                *frames = frames.saturating_add(1);
+
+                if *bg <= 0.0 {
+                    *bg = dist;
+                } else {
+                    *bg += (dist - *bg) / *frames as f32;
+                }
+            }
+        }
+    }
+
+    /// Slowly adapts the background to the measurement.
+    ///
+    /// Zones with background in range are adapted only when they are not in the foreground
+    /// (at least `min_diff_dist` closer than the background), so that a hand is never learned into the background.
+    /// `rate` is the adaption rate per frame, in range `[0.0, 1.0]`.
+    ///
+    /// Zones without background take on a static object once it persisted within `min_diff_dist`
+    /// for `persist_frames` consecutive frames. Zero disables this.
+    pub fn adapt(
+        &mut self,
+        measurement: &SensorMeasurement<RES_X, RES_Y>,
+        min_diff_dist: f32,
+        rate: f32,
+        persist_frames: u32,
+    ) {
+        if !self.is_calibrated() {
+            return;
+        }
+
+        for (pos_y, row) in measurement.zone_dist.iter().enumerate() {
+            for (pos_x, &dist) in row.iter().enumerate() {
+                let bg = &mut self.zone_dist[pos_y][pos_x];
+                let candidate = &mut self.candidate_dist[pos_y][pos_x];
+                let candidate_frames = &mut self.candidate_frames[pos_y][pos_x];
+
+                if *bg <= 0.0 {
+                    if dist <= 0.0 {
+                        *candidate = -1.0;
+                        *candidate_frames = 0;
+                    } else if *candidate > 0.0 && libm::fabsf(dist - *candidate) < min_diff_dist {
+                        *candidate_frames = candidate_frames.saturating_add(1);
+                        *candidate += (dist - *candidate) / *candidate_frames as f32;
+                    } else {
+                        *candidate = dist;
+                        *candidate_frames = 1;
+                    }
+
+                    if persist_frames > 0 && *candidate_frames as u32 >= persist_frames {
+                        *bg = *candidate;
+                        *candidate = -1.0;
+                        *candidate_frames = 0;
+                    }
+                    continue;
+                }
+                if dist > 0.0 && dist <= *bg - min_diff_dist {
+                    continue;
+                }
+                // Invalid values are adapted as if they were at the furthest distance
+                let dist = if dist > 0.0 { dist } else { Self::MAX_DIST };
+
+                let adapted = *bg + rate * (dist - *bg);
+                // The adaption only approaches the furthest distance, so it is out of range shortly before
+                *bg = if adapted < Self::MAX_DIST - min_diff_dist {
+                    adapted
+                } else {
+                    -1.0
+                };
+            }
+        }
+    }
+
+    /// Subtracts the background from the measurement.
+    ///
+    /// Only zones that are at least `min_diff_dist` closer than the background stay valid, all others are set to -1.0.
+    pub fn subtract(
+        &self,
+        mut measurement: SensorMeasurement<RES_X, RES_Y>,
+        min_diff_dist: f32,
+    ) -> SensorMeasurement<RES_X, RES_Y> {
+        if !self.is_calibrated() {
+            return measurement;
+        }
+
+        for (bg_row, row) in self.zone_dist.iter().zip(measurement.zone_dist.iter_mut()) {
+            for (&bg, dist) in bg_row.iter().zip(row.iter_mut()) {
+                if bg > 0.0 && *dist > bg - min_diff_dist {
+                    *dist = -1.0;
+                }
+            }
+        }
+
+        measurement
+    }
+}
+
+/// The median of the valid values (> 0.0), only when at least two of the three values are valid.
+///
+/// Returns -1.0 else.
+fn median3_valid(values: [f32; 3]) -> f32 {
+    let mut valid = [0.0; 3];
+    let mut n_valid = 0;
+
+    for v in values {
+        if v > 0.0 {
+            valid[n_valid] = v;
+            n_valid += 1;
+        }
+    }
+
+    match n_valid {
+        2 => (valid[0] + valid[1]) / 2.0,
+        3 => valid[0]
+            .max(valid[1])
+            .min(valid[2])
+            .max(valid[0].min(valid[1])),
+        _ => -1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BackgroundModel, ZoneFilter, ZoneFilterMode};
+    use crate::SensorMeasurement;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn zone_filter_median3() {
+        let mut filter = ZoneFilter::<2, 1>::new(ZoneFilterMode::ZoneFilterMedian3, 0);
+        let inputs = [[100.0, -1.0], [110.0, 200.0], [-1.0, -1.0], [90.0, -1.0]];
+        let expected = [[-1.0, -1.0], [105.0, -1.0], [105.0, -1.0], [100.0, -1.0]];
+
+        for (input, expected) in inputs.into_iter().zip(expected) {
+            let filtered = filter.filter(SensorMeasurement::new([input]));
+
+            assert_relative_eq!(filtered.zone_dist[0][0], expected[0]);
+            assert_relative_eq!(filtered.zone_dist[0][1], expected[1]);
+        }
+    }
+
+    #[test]
+    fn zone_filter_hysteresis() {
+        let mut filter = ZoneFilter::<1, 1>::new(ZoneFilterMode::ZoneFilterHysteresis, 2);
+        let inputs = [100.0, -1.0, 120.0, 130.0, -1.0, -1.0, 140.0];
+        let expected = [-1.0, -1.0, -1.0, 130.0, 130.0, -1.0, -1.0];
+
+        for (input, expected) in inputs.into_iter().zip(expected) {
+            let filtered = filter.filter(SensorMeasurement::new([[input]]));
+
+            assert_relative_eq!(filtered.zone_dist[0][0], expected);
+        }
+    }
+
+    #[test]
+    fn background_subtraction() {
+        let mut background = BackgroundModel::<4, 1>::empty();
+        background.learn(&SensorMeasurement::new([[300.0, 500.0, -1.0, -1.0]]));
+        background.learn(&SensorMeasurement::new([[320.0, 500.0, 600.0, -1.0]]));
+        background.learn(&SensorMeasurement::new([[-1.0, 500.0, 620.0, -1.0]]));
+
+        assert_eq!(background.frames, 3);
+        assert_eq!(background.zone_frames, [[2, 3, 2, 0]]);
+        // Zones are averaged over their own valid distances
+        assert_relative_eq!(background.zone_dist[0][0], 310.0);
+        assert_relative_eq!(background.zone_dist[0][2], 610.0);
+
+        let subtracted =
+            background.subtract(SensorMeasurement::new([[290.0, 200.0, 250.0, 700.0]]), 50.0);
+        assert_eq!(subtracted.zone_dist, [[-1.0, 200.0, 250.0, 700.0]]);
+
+        // A hand in front of the background is never learned, zones without background stay without
+        for _ in 0..1000 {
+            background.adapt(
+                &SensorMeasurement::new([[340.0, 200.0, 250.0, 700.0]]),
+                50.0,
+                0.01,
+                0,
+            );
+        }
+        assert_relative_eq!(background.zone_dist[0][0], 340.0, epsilon = 1.0);
+        assert_relative_eq!(background.zone_dist[0][1], 500.0);
+        assert_relative_eq!(background.zone_dist[0][2], 610.0);
+        assert_relative_eq!(background.zone_dist[0][3], -1.0);
+
+        // Background that is removed goes out of range over time
+        for _ in 0..1000 {
+            background.adapt(
+                &SensorMeasurement::new([[340.0, -1.0, 610.0, -1.0]]),
+                50.0,
+                0.01,
+                0,
+            );
+        }
+        assert_relative_eq!(background.zone_dist[0][1], -1.0);
+    }
+
+    #[test]
+    fn background_persistent_object() {
+        let mut background = BackgroundModel::<3, 1>::empty();
+        background.learn(&SensorMeasurement::new([[500.0, -1.0, -1.0]]));
+
+        // An object placed in the empty zones after the calibration, one of them only briefly interrupted
+        for frame in 0..20 {
+            let interrupted = if frame == 5 {
+                600.0
+            } else {
+                300.0 + frame as f32
+            };
+            background.adapt(
+                &SensorMeasurement::new([[500.0, 300.0 + frame as f32, interrupted]]),
+                50.0,
+                0.01,
+                10,
+            );
+            assert_eq!(background.zone_dist[0][1] > 0.0, frame >= 9, "{frame}");
+            // The interrupted zone persisted only after the interruption
+            assert_eq!(background.zone_dist[0][2] > 0.0, frame >= 15, "{frame}");
+            // The object is learned with its mean distance
+            if frame == 9 {
+                assert_relative_eq!(background.zone_dist[0][1], 304.5);
+            } else if frame == 15 {
+                assert_relative_eq!(background.zone_dist[0][2], 310.5);
+            }
+        }
+        // The object is no longer in the foreground, but a hand in front of it is
+        assert_eq!(
+            background
+                .subtract(SensorMeasurement::new([[500.0, 310.0, 250.0]]), 50.0)
+                .zone_dist,
+            [[-1.0, -1.0, 250.0]]
+        );
+
+        // Disabled
+        let mut background = BackgroundModel::<1, 1>::empty();
+        background.learn(&SensorMeasurement::new([[-1.0]]));
+        for _ in 0..1000 {
+            background.adapt(&SensorMeasurement::new([[300.0]]), 50.0, 0.01, 0);
+        }
+        assert_relative_eq!(background.zone_dist[0][0], -1.0);
+    }
+}
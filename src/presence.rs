@@ -0,0 +1,128 @@
+//! Presence Detection.
+//!
+//! All distance values are considered to be in millimeter.
+
+use crate::SensorMeasurement;
+
+/// A presence event.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PresenceEvent {
+    /// The presence did not change.
+    PresenceNone = 0,
+    /// Something entered the range of the sensor.
+    PresenceEntered,
+    /// Nothing is in the range of the sensor anymore.
+    PresenceLeft,
+}
+
+/// Cheaply detects the presence of something in the range of the sensor, debounced over several frames.
+///
+/// Only the distance of the nearest zone is compared to the approach threshold.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PresenceDetector {
+    threshold_dist: f32,
+    enter_frames: u32,
+    leave_frames: u32,
+    present: bool,
+    /// The number of consecutive frames contradicting the current presence.
+    counter: u32,
+}
+
+impl PresenceDetector {
+    /// A new presence detector, initially nothing is present.
+    ///
+    /// Arguments:
+    /// - threshold_dist: the approach threshold distance of the nearest zone
+    /// - enter_frames: the number of consecutive frames within the threshold until presence is entered
+    /// - leave_frames: the number of consecutive frames beyond the threshold until presence is left
+    pub fn new(threshold_dist: f32, enter_frames: u32, leave_frames: u32) -> Self {
+        Self {
+            threshold_dist,
+            enter_frames,
+            leave_frames,
+            present: false,
+            counter: 0,
+        }
+    }
+
+    /// Resets the detector, nothing is then considered present.
+    pub fn reset(&mut self) {
+        self.present = false;
+        self.counter = 0;
+    }
+
+    /// Whether something is currently present.
+    pub fn is_present(&self) -> bool {
+        self.present
+    }
+
+    /// Updates the detector with a new measurement.
+    ///
+    /// Returns the presence event, [PresenceEvent::PresenceNone] if the presence did not change.
+    pub fn update<const RES_X: usize, const RES_Y: usize>(
+        &mut self,
+        measurement: &SensorMeasurement<RES_X, RES_Y>,
+    ) -> PresenceEvent {
+        let (_, nearest_dist) = measurement.min_dist();
+        let within = nearest_dist > 0.0 && nearest_dist <= self.threshold_dist;
+
+        if within == self.present {
+            self.counter = 0;
+            return PresenceEvent::PresenceNone;
+        }
+
+        self.counter += 1;
+
+        if self.present && self.counter >= self.leave_frames {
+            self.present = false;
+            self.counter = 0;
+            PresenceEvent::PresenceLeft
+        } else if !self.present && self.counter >= self.enter_frames {
+            self.present = true;
+            self.counter = 0;
+            PresenceEvent::PresenceEntered
+        } else {
+            PresenceEvent::PresenceNone
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PresenceDetector, PresenceEvent};
+    use crate::SensorMeasurement;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn presence_debounce() {
+        let mut detector = PresenceDetector::new(500.0, 2, 3);
+        let inputs = [
+            -1.0, 300.0, -1.0, 300.0, 400.0, 900.0, -1.0, 300.0, -1.0, -1.0, -1.0,
+        ];
+        let expected = [
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceEntered,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceNone,
+            PresenceEvent::PresenceLeft,
+        ];
+
+        for (input, expected) in inputs.into_iter().zip(expected) {
+            assert_eq!(
+                detector.update(&SensorMeasurement::new([[input]])),
+                expected
+            );
+        }
+        assert!(!detector.is_present());
+    }
+}
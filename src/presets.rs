@@ -0,0 +1,208 @@
+//! Sensor Presets.
+//!
+//! Parameters for common multizone TOF-Sensors, with recommended recognizer parameters for their typical frame rate and range.
+//! The FOV values are the nominal values from the datasheets of the sensors.
+
+use crate::measurements::SensorParams;
+use crate::RecognizerParams;
+
+/// The available sensor presets.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SensorPresetId {
+    /// ST VL53L5CX in 4x4 mode.
+    SensorPresetVl53l5cx4x4 = 0,
+    /// ST VL53L5CX in 8x8 mode.
+    SensorPresetVl53l5cx8x8,
+    /// ST VL53L7CX in 4x4 mode.
+    SensorPresetVl53l7cx4x4,
+    /// ST VL53L7CX in 8x8 mode.
+    SensorPresetVl53l7cx8x8,
+    /// ST VL53L8CX in 4x4 mode.
+    SensorPresetVl53l8cx4x4,
+    /// ST VL53L8CX in 8x8 mode.
+    SensorPresetVl53l8cx8x8,
+    /// ams TMF8820 in 3x3 mode.
+    SensorPresetTmf88203x3,
+    /// ams TMF8821 in 4x4 mode.
+    SensorPresetTmf88214x4,
+    /// ams TMF8828 in 8x8 mode.
+    SensorPresetTmf88288x8,
+}
+
+/// A sensor preset: the sensor parameters with the matching recommended recognizer parameters.
+///
+/// The recognizer must be created with the resolution of the preset and a history size of at least `min_history_size`,
+/// e.g. the 4x4 presets at 60 Hz need a history size of 91 for the static hold time of 1.5 seconds.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SensorPreset {
+    /// The horizontal resolution of the sensor mode.
+    pub res_x: u32,
+    /// The vertical resolution of the sensor mode.
+    pub res_y: u32,
+    /// The typical frame rate of the sensor mode (Hz).
+    pub frame_rate_hz: f32,
+    /// The maximum ranging distance of the sensor from its datasheet (mm).
+    ///
+    /// Informational, the threshold distances of the recognizer parameters do not depend on it.
+    pub max_dist: f32,
+    /// The minimum history size for the recognizer parameters at the frame rate, see [min_history_size].
+    pub min_history_size: u32,
+    /// The sensor parameters.
+    pub sensor_params: SensorParams,
+    /// The recommended recognizer parameters.
+    pub recognizer_params: RecognizerParams,
+}
+
+impl SensorPreset {
+    /// Gets the preset with the given id.
+    pub fn get(id: SensorPresetId) -> Self {
+        match id {
+            SensorPresetId::SensorPresetVl53l5cx4x4 => {
+                Self::new(4, 60.0, 4000.0, SensorParams::default_vl53l5cx())
+            }
+            SensorPresetId::SensorPresetVl53l5cx8x8 => {
+                Self::new(8, 15.0, 4000.0, SensorParams::default_vl53l5cx())
+            }
+            SensorPresetId::SensorPresetVl53l7cx4x4 => {
+                Self::new(4, 60.0, 3500.0, SensorParams::default_vl53l7cx())
+            }
+            SensorPresetId::SensorPresetVl53l7cx8x8 => {
+                Self::new(8, 15.0, 3500.0, SensorParams::default_vl53l7cx())
+            }
+            SensorPresetId::SensorPresetVl53l8cx4x4 => {
+                Self::new(4, 60.0, 4000.0, SensorParams::default_vl53l8cx())
+            }
+            SensorPresetId::SensorPresetVl53l8cx8x8 => {
+                Self::new(8, 15.0, 4000.0, SensorParams::default_vl53l8cx())
+            }
+            SensorPresetId::SensorPresetTmf88203x3 => {
+                Self::new(3, 30.0, 5000.0, SensorParams::default_tmf882x())
+            }
+            SensorPresetId::SensorPresetTmf88214x4 => {
+                Self::new(4, 30.0, 5000.0, SensorParams::default_tmf882x())
+            }
+            SensorPresetId::SensorPresetTmf88288x8 => {
+                Self::new(8, 10.0, 5000.0, SensorParams::default_tmf882x())
+            }
+        }
+    }
+
+    fn new(res: u32, frame_rate_hz: f32, max_dist: f32, sensor_params: SensorParams) -> Self {
+        let recognizer_params = recommended_recognizer_params(res, frame_rate_hz);
+
+        Self {
+            res_x: res,
+            res_y: res,
+            frame_rate_hz,
+            max_dist,
+            min_history_size: min_history_size(&recognizer_params, frame_rate_hz),
+            sensor_params,
+            recognizer_params,
+        }
+    }
+}
+
+/// The swipe is recognized from the hand positions of the last 600ms.
+const SWIPE_TIME_MS: u32 = 600;
+
+/// The minimum history size, so that the history spans the longest duration of the recognizer parameters
+/// (the static hold, grab, rotate and swipe times) at the given frame rate.
+///
+/// A smaller history cuts these durations short, e.g. a static hold is then never recognized.
+pub fn min_history_size(recognizer_params: &RecognizerParams, frame_rate_hz: f32) -> u32 {
+    let max_time_ms = recognizer_params
+        .static_hold_time_ms
+        .max(recognizer_params.grab_time_ms)
+        .max(recognizer_params.rotate_time_ms)
+        .max(SWIPE_TIME_MS);
+
+    // One more entry than frame periods, since the oldest entry must be at least as old as the duration
+    libm::ceilf(max_time_ms as f32 * frame_rate_hz / 1000.0) as u32 + 1
+}
+
+/// The recommended recognizer parameters for the given resolution and frame rate.
+///
+/// Parameters counted in frames are derived from durations, so that they behave the same at every frame rate.
+/// The threshold distances are the defaults, since the hand distance does not depend on the range of the sensor.
+fn recommended_recognizer_params(res: u32, frame_rate_hz: f32) -> RecognizerParams {
+    let frames = |duration_s: f32| (libm::roundf(duration_s * frame_rate_hz) as u32).max(1);
+    let defaults = RecognizerParams::default();
+
+    RecognizerParams {
+        // Predict through two frames without a hand, with a margin of half a frame period for jitter
+        tracker_max_dropout_ms: defaults
+            .tracker_max_dropout_ms
+            .max((2500.0 / frame_rate_hz) as u32),
+        zone_filter_hysteresis_frames: frames(0.1),
+        presence_enter_frames: frames(0.1).max(2),
+        presence_leave_frames: frames(1.0),
+        // The hand position of coarse grids jumps more, when the hand moves between zones
+        swipe_tolerance_dist: if res <= 4 {
+            150.0
+        } else {
+            defaults.swipe_tolerance_dist
+        },
+        ..defaults
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{min_history_size, SensorPreset, SensorPresetId};
+    use crate::RecognizerParams;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn preset_frame_rate_params() {
+        let fast = SensorPreset::get(SensorPresetId::SensorPresetVl53l5cx4x4);
+        let slow = SensorPreset::get(SensorPresetId::SensorPresetVl53l5cx8x8);
+
+        assert_eq!((fast.res_x, fast.res_y), (4, 4));
+        assert_eq!((slow.res_x, slow.res_y), (8, 8));
+        // Both leave the presence after one second
+        assert_eq!(fast.recognizer_params.presence_leave_frames, 60);
+        assert_eq!(slow.recognizer_params.presence_leave_frames, 15);
+        assert_eq!(slow.recognizer_params.tracker_max_dropout_ms, 166);
+    }
+
+    #[test]
+    fn preset_range_params() {
+        let short = SensorPreset::get(SensorPresetId::SensorPresetVl53l7cx8x8);
+        let long = SensorPreset::get(SensorPresetId::SensorPresetTmf88288x8);
+        let defaults = RecognizerParams::default();
+
+        // The hand distance does not depend on the range of the sensor
+        for preset in [short, long] {
+            assert_eq!(
+                preset.recognizer_params.gesture_threshold_dist,
+                defaults.gesture_threshold_dist
+            );
+            assert_eq!(
+                preset.recognizer_params.adaptive_threshold_max_dist,
+                defaults.adaptive_threshold_max_dist
+            );
+            assert_eq!(
+                preset.recognizer_params.presence_threshold_dist,
+                defaults.presence_threshold_dist
+            );
+        }
+    }
+
+    #[test]
+    fn preset_min_history_size() {
+        let fast = SensorPreset::get(SensorPresetId::SensorPresetVl53l5cx4x4);
+        let slow = SensorPreset::get(SensorPresetId::SensorPresetTmf88288x8);
+
+        // The static hold time of 1.5 seconds is the longest duration
+        assert_eq!(fast.min_history_size, 91);
+        assert_eq!(slow.min_history_size, 16);
+        assert_eq!(
+            min_history_size(&RecognizerParams::default(), 15.0),
+            SensorPreset::get(SensorPresetId::SensorPresetVl53l5cx8x8).min_history_size
+        );
+    }
+}
@@ -2,14 +2,24 @@
 //!
 //! All distance values are considered to be in millimeter.
 
-use crate::math::CoordsCartesian;
-use crate::measurements::SensorParams;
-use crate::{measurements, HandState, SensorMeasurement};
+use core::f32::consts::{FRAC_PI_2, PI};
+
+use crate::calibration::ZoneCalibration;
+use crate::math::{
+    self, CoordsCartesian, CoordsSpherical, EmaFilter, MedianFilter, OneEuroFilter, Scalar,
+};
+use crate::measurements::{MultiTargetMeasurement, SensorParams, ZoneGeometry};
+use crate::preprocessing::{BackgroundModel, ZoneFilter, ZoneFilterMode};
+use crate::presence::{PresenceDetector, PresenceEvent};
+use crate::tracker::HandTracker;
+use crate::{measurements, HandState, SensorMeasurement, ZoneQuality};
 
 /// A hand gesture.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Gesture {
     /// No recognized gesture.
     GestureNone = 0,
@@ -23,16 +33,48 @@ pub enum Gesture {
     GestureSwipeUp,
     /// A down swipe.
     GestureSwipeDown,
+    /// A grab, the hand closes (e.g. from open palm to fist).
+    GestureGrab,
+    /// A release, the hand opens (e.g. from fist to open palm).
+    GestureRelease,
+    /// A clockwise rotation of the hand when looking at the sensor, like turning a key.
+    GestureRotateClockwise,
+    /// A counterclockwise rotation of the hand when looking at the sensor, like turning a key.
+    GestureRotateCounterClockwise,
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Gesture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Gesture::GestureNone => "none",
+            Gesture::GestureStaticHold => "static hold",
+            Gesture::GestureSwipeRight => "swipe right",
+            Gesture::GestureSwipeLeft => "swipe left",
+            Gesture::GestureSwipeUp => "swipe up",
+            Gesture::GestureSwipeDown => "swipe down",
+            Gesture::GestureGrab => "grab",
+            Gesture::GestureRelease => "release",
+            Gesture::GestureRotateClockwise => "rotate clockwise",
+            Gesture::GestureRotateCounterClockwise => "rotate counterclockwise",
+        };
+
+        f.write_str(name)
+    }
 }
 
 /// A gesture prediction result.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RecognizerResult {
     /// The current hand state.
     pub hand_state: HandState,
     /// The recognized gesture, GestureNone if no gesture was recognized.
     pub gesture: Gesture,
+    /// The presence event in presence mode, PresenceNone if the presence did not change.
+    pub presence_event: PresenceEvent,
 }
 
 impl Default for RecognizerResult {
@@ -40,15 +82,51 @@ impl Default for RecognizerResult {
         Self {
             hand_state: HandState::HandNotFound,
             gesture: Gesture::GestureNone,
+            presence_event: PresenceEvent::PresenceNone,
         }
     }
 }
 
+/// The filter that is applied to smooth the recognized hand position.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HandPosFilter {
+    /// The hand position is not smoothed.
+    HandPosFilterNone = 0,
+    /// Exponential moving average.
+    HandPosFilterEma,
+    /// One-euro filter, adapting the smoothing to the speed of the hand.
+    HandPosFilterOneEuro,
+    /// Median over a window of the last positions.
+    HandPosFilterMedian,
+}
+
+/// How the furthest hand distance for gesture recognition is determined.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ThresholdMode {
+    /// The fixed configured threshold distance.
+    ThresholdModeFixed = 0,
+    /// The threshold distance adapts to the observed background depth and hand distances, within the configured bounds.
+    ThresholdModeAdaptive,
+}
+
+/// The maximum window size of the median hand position filter.
+pub const HAND_POS_FILTER_MEDIAN_MAX_WINDOW: usize = 9;
+
 /// Parameters for gesture recognition.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RecognizerParams {
     /// The furthest hand distance for gesture recognition.
+    ///
+    /// Only used with [ThresholdMode::ThresholdModeFixed].
     pub gesture_threshold_dist: f32,
     /// The time the hand has to be still to recognize a static hold.
     pub static_hold_time_ms: u32,
@@ -60,6 +138,102 @@ pub struct RecognizerParams {
     pub swipe_horizontal_travel_dist: f32,
     /// How much distance the hand has to travel to detect a vertical swipe.
     pub swipe_vertical_travel_dist: f32,
+    /// Enables the hand tracker, that filters the hand position and predicts it through short dropouts.
+    ///
+    /// When disabled, the tracker is not updated.
+    pub tracker_enabled: bool,
+    /// The process noise of the hand tracker, the standard deviation of the hand acceleration (mm/s^2).
+    pub tracker_accel_noise: f32,
+    /// The measurement noise of the hand tracker, the standard deviation of the measured hand position.
+    pub tracker_measurement_noise: f32,
+    /// How long the hand tracker predicts the hand position while the hand is temporarily not found.
+    pub tracker_max_dropout_ms: u32,
+    /// The filter that smoothes the hand position before it is put into the result and history.
+    pub hand_pos_filter: HandPosFilter,
+    /// The smoothing factor of the exponential moving average filter, in range `(0.0, 1.0]`.
+    pub hand_pos_filter_ema_alpha: f32,
+    /// The minimum cutoff frequency of the one-euro filter (Hz).
+    pub hand_pos_filter_one_euro_min_cutoff: f32,
+    /// How much the cutoff frequency of the one-euro filter increases with the hand speed.
+    pub hand_pos_filter_one_euro_beta: f32,
+    /// The cutoff frequency of the hand speed of the one-euro filter (Hz).
+    pub hand_pos_filter_one_euro_d_cutoff: f32,
+    /// The window size of the median filter, up to [HAND_POS_FILTER_MEDIAN_MAX_WINDOW].
+    pub hand_pos_filter_median_window: u32,
+    /// The temporal filter that is applied to every zone of the measurements before the hand is recognized.
+    pub zone_filter: ZoneFilterMode,
+    /// The number of consecutive frames until a zone changes between valid and invalid, for the hysteresis zone filter.
+    pub zone_filter_hysteresis_frames: u32,
+    /// How much closer than the calibrated background a zone has to be to count towards hand recognition.
+    pub background_min_diff_dist: f32,
+    /// How fast the calibrated background adapts to changes of the static scene, per frame in range `[0.0, 1.0]`.
+    pub background_adapt_rate: f32,
+    /// The number of consecutive frames a static object has to persist in a zone without calibrated background
+    /// until it is learned into the background. Zero disables this.
+    ///
+    /// It should be much longer than any gesture, so that a hand held still is not learned.
+    pub background_persist_frames: u32,
+    /// How the furthest hand distance for gesture recognition is determined.
+    pub gesture_threshold_mode: ThresholdMode,
+    /// The lower bound of the adaptive threshold distance.
+    pub adaptive_threshold_min_dist: f32,
+    /// The upper bound of the adaptive threshold distance.
+    pub adaptive_threshold_max_dist: f32,
+    /// How far in front of the observed background depth the adaptive threshold distance stays.
+    pub adaptive_threshold_background_margin: f32,
+    /// How far behind the average observed hand distance the adaptive threshold distance is.
+    pub adaptive_threshold_hand_margin: f32,
+    /// How fast the adaptive threshold distance follows the observed distances, per frame in range `[0.0, 1.0]`.
+    pub adaptive_threshold_rate: f32,
+    /// How far behind the nearest zone the zones connected to it still belong to the hand.
+    pub hand_depth_range: f32,
+    /// The largest area of a hand (mm^2). Larger objects, e.g. forearms or bodies walking past, are rejected.
+    ///
+    /// Zero disables the rejection.
+    pub hand_max_area: f32,
+    /// The largest area of a fist (mm^2). Larger hands are classified as open palm.
+    ///
+    /// Its square root is the typical width of a fist, compact hands up to 1.5 times as wide are classified as fist.
+    pub hand_fist_max_area: f32,
+    /// The largest area of a pointing finger (mm^2).
+    ///
+    /// Its square root is the largest width of a finger, wider hands are not classified as finger.
+    pub hand_finger_max_area: f32,
+    /// The time in which the hand area has to change to detect a grab or release.
+    ///
+    /// Each half of it must contain at least three measurements with a hand.
+    pub grab_time_ms: u32,
+    /// The ratio of the newer to the older hand area at or below which a grab is detected.
+    /// A release is detected at or above its inverse.
+    pub grab_area_ratio: f32,
+    /// How much the hand can move while doing a grab or release.
+    pub grab_tolerance_dist: f32,
+    /// The time in which the hand has to rotate to detect a rotate gesture.
+    pub rotate_time_ms: u32,
+    /// How much the hand has to rotate to detect a rotate gesture (deg).
+    pub rotate_angle: f32,
+    /// How much the hand can move while doing a rotate gesture.
+    pub rotate_tolerance_dist: f32,
+    /// How elongated the hand has to be for its orientation to be considered, in range `[0.0, 1.0]`.
+    pub rotate_min_elongation: f32,
+    /// Enables the low-power presence mode.
+    ///
+    /// Only the nearest zone distance is checked against the presence threshold, after the zone mask and the
+    /// calibrated background were applied. The zone filter and the full gesture recognition only run while
+    /// something is present.
+    ///
+    /// Not supported by the fusion gesture recognizer, which fails to initialize and reset when it is enabled.
+    pub presence_mode: bool,
+    /// The approach threshold distance of the nearest zone for presence detection.
+    pub presence_threshold_dist: f32,
+    /// The number of consecutive frames within the presence threshold until presence is entered.
+    pub presence_enter_frames: u32,
+    /// The number of consecutive frames beyond the presence threshold until presence is left.
+    pub presence_leave_frames: u32,
+    /// The maximum distance between the hands found by multiple sensors to be fused into one hand.
+    ///
+    /// Hands farther away from the nearest hand are ignored by the fusion gesture recognizer.
+    pub fusion_max_hand_dist: f32,
 }
 
 impl Default for RecognizerParams {
@@ -71,6 +245,43 @@ impl Default for RecognizerParams {
             swipe_tolerance_dist: 120.0,
             swipe_horizontal_travel_dist: 80.0,
             swipe_vertical_travel_dist: 70.0,
+            tracker_enabled: false,
+            tracker_accel_noise: 10000.0,
+            tracker_measurement_noise: 15.0,
+            tracker_max_dropout_ms: 150,
+            hand_pos_filter: HandPosFilter::HandPosFilterNone,
+            hand_pos_filter_ema_alpha: 0.5,
+            hand_pos_filter_one_euro_min_cutoff: 1.0,
+            hand_pos_filter_one_euro_beta: 0.01,
+            hand_pos_filter_one_euro_d_cutoff: 1.0,
+            hand_pos_filter_median_window: 3,
+            zone_filter: ZoneFilterMode::ZoneFilterNone,
+            zone_filter_hysteresis_frames: 2,
+            background_min_diff_dist: 100.0,
+            background_adapt_rate: 0.001,
+            background_persist_frames: 300,
+            gesture_threshold_mode: ThresholdMode::ThresholdModeFixed,
+            adaptive_threshold_min_dist: 150.0,
+            adaptive_threshold_max_dist: 800.0,
+            adaptive_threshold_background_margin: 150.0,
+            adaptive_threshold_hand_margin: 200.0,
+            adaptive_threshold_rate: 0.01,
+            hand_depth_range: 80.0,
+            hand_max_area: 0.0,
+            hand_fist_max_area: 9000.0,
+            hand_finger_max_area: 2500.0,
+            grab_time_ms: 600,
+            grab_area_ratio: 0.5,
+            grab_tolerance_dist: 60.0,
+            rotate_time_ms: 800,
+            rotate_angle: 45.0,
+            rotate_tolerance_dist: 50.0,
+            rotate_min_elongation: 0.3,
+            presence_mode: false,
+            presence_threshold_dist: 800.0,
+            presence_enter_frames: 2,
+            presence_leave_frames: 15,
+            fusion_max_hand_dist: 150.0,
         }
     }
 }
@@ -79,6 +290,7 @@ impl Default for RecognizerParams {
 #[repr(C)]
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum RecognizerStatus {
     /// Ok.
     RecognizerStatusOk = 0,
@@ -88,235 +300,1031 @@ pub enum RecognizerStatus {
     RecognizerStatusInvalidInput,
 }
 
+impl From<RecognizerError> for RecognizerStatus {
+    fn from(error: RecognizerError) -> Self {
+        match error {
+            RecognizerError::InitFailure => RecognizerStatus::RecognizerStatusInitFailure,
+            RecognizerError::InvalidInput => RecognizerStatus::RecognizerStatusInvalidInput,
+        }
+    }
+}
+
+impl RecognizerStatus {
+    /// Converts the status into a result, to propagate failures with `?`.
+    pub fn into_result(self) -> Result<(), RecognizerError> {
+        match self {
+            RecognizerStatus::RecognizerStatusOk => Ok(()),
+            RecognizerStatus::RecognizerStatusInitFailure => Err(RecognizerError::InitFailure),
+            RecognizerStatus::RecognizerStatusInvalidInput => Err(RecognizerError::InvalidInput),
+        }
+    }
+}
+
+/// A failure of the gesture recognizer, see [RecognizerStatus::into_result].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RecognizerError {
+    /// The recognizer initialization failed.
+    InitFailure,
+    /// The input to the recognizer was invalid.
+    InvalidInput,
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for RecognizerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            RecognizerError::InitFailure => "recognizer initialization failed",
+            RecognizerError::InvalidInput => "invalid input to the recognizer",
+        };
+
+        f.write_str(description)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecognizerError {}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct HistoryEntry<const RES_X: usize, const RES_Y: usize> {
-    measurement: SensorMeasurement<RES_X, RES_Y>,
-    hand_state: HandState,
+    /// The time of the measurement in milliseconds.
+    pub(crate) time_ms: u32,
+    /// The index of the sensor the zone distances are from.
+    pub(crate) sensor: usize,
+    /// The distances of each zone of the preprocessed measurement.
+    pub(crate) zone_dist: [[f32; RES_X]; RES_Y],
+    pub(crate) hand_state: HandState,
 }
 
 impl<const RES_X: usize, const RES_Y: usize> HistoryEntry<RES_X, RES_Y> {
     pub fn invalid() -> Self {
         Self {
-            measurement: SensorMeasurement::invalid(),
+            time_ms: 0,
+            sensor: 0,
+            zone_dist: [[-1.0; RES_X]; RES_Y],
             hand_state: HandState::HandNotFound,
         }
     }
 }
 
-/// The gesture recognizer.
+/// Adapts the threshold distance to the observed background depth and hand distances.
 ///
-/// Is initially configured through parameters and gets fed measurements and time and predicts gestures.
+/// The threshold stays in front of the background and follows the average hand distance with a margin,
+/// so that it narrows when the hands are close to the sensor and widens when they are further away.
 #[repr(C)]
-#[derive(Debug, Clone)]
-pub struct GestureRecognizer<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize> {
-    params: RecognizerParams,
-    sensor_params: SensorParams,
-    start_time: u32,
-    history: [HistoryEntry<RES_X, RES_Y>; HISTORY_SIZE],
-    received_measurements: usize,
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AdaptiveThreshold {
+    /// The observed background depth (mm).
+    background_dist: f32,
+    /// The average observed hand distance (mm), negative when no hand was observed yet.
+    hand_dist: f32,
 }
 
-impl<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize>
-    GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>
-{
-    /// A new gesture recognizer initialized with the given parameters.
+impl AdaptiveThreshold {
+    fn new(params: &RecognizerParams) -> Self {
+        Self {
+            background_dist: params.adaptive_threshold_max_dist
+                + params.adaptive_threshold_background_margin,
+            hand_dist: -1.0,
+        }
+    }
+
+    /// The current threshold distance.
+    fn threshold_dist(&self, params: &RecognizerParams) -> f32 {
+        let mut threshold = self.background_dist - params.adaptive_threshold_background_margin;
+
+        if self.hand_dist > 0.0 {
+            threshold = threshold.min(self.hand_dist + params.adaptive_threshold_hand_margin);
+        }
+
+        threshold.clamp(
+            params.adaptive_threshold_min_dist,
+            params
+                .adaptive_threshold_max_dist
+                .max(params.adaptive_threshold_min_dist),
+        )
+    }
+
+    /// Updates the observed distances.
     ///
-    /// The sensor parameters have preconfigured defaults for common TOF-Sensors such as the ST VL53L5CX.
-    pub fn new(params: RecognizerParams, sensor_params: SensorParams) -> Self {
+    /// The background depth is taken from the calibrated background if available,
+    /// else it slowly follows the nearest distance of the measurements in which no hand was found,
+    /// so that the threshold does not collapse onto the hand.
+    fn update<const RES_X: usize, const RES_Y: usize>(
+        &mut self,
+        params: &RecognizerParams,
+        measurement: &SensorMeasurement<RES_X, RES_Y>,
+        background: &BackgroundModel<RES_X, RES_Y>,
+        hand_state: HandState,
+    ) {
+        let max_dist =
+            params.adaptive_threshold_max_dist + params.adaptive_threshold_background_margin;
+        let rate = params.adaptive_threshold_rate;
+
+        if background.is_calibrated() {
+            let (_, background_dist) = measurements::zone_dist_min(&background.zone_dist);
+            self.background_dist = background_dist.min(max_dist);
+        }
+
+        match hand_state {
+            HandState::HandFound { hand_pos, .. } => {
+                if self.hand_dist > 0.0 {
+                    self.hand_dist += rate * (hand_pos.r - self.hand_dist);
+                } else {
+                    self.hand_dist = hand_pos.r;
+                }
+            }
+            HandState::HandNotFound if !background.is_calibrated() => {
+                let (_, nearest_dist) = measurement.min_dist();
+                self.background_dist += rate * (nearest_dist.min(max_dist) - self.background_dist);
+            }
+            HandState::HandNotFound => {}
+        }
+    }
+}
+
+/// Smoothes the hand position with the configured filter, independently for every cartesian axis.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HandPosSmoother {
+    kind: HandPosFilter,
+    ema: [EmaFilter; 3],
+    one_euro: [OneEuroFilter; 3],
+    median: [MedianFilter<HAND_POS_FILTER_MEDIAN_MAX_WINDOW>; 3],
+}
+
+impl HandPosSmoother {
+    pub(crate) fn new(params: &RecognizerParams) -> Self {
         Self {
-            params,
+            kind: params.hand_pos_filter,
+            ema: [EmaFilter::new(params.hand_pos_filter_ema_alpha); 3],
+            one_euro: [OneEuroFilter::new(
+                params.hand_pos_filter_one_euro_min_cutoff,
+                params.hand_pos_filter_one_euro_beta,
+                params.hand_pos_filter_one_euro_d_cutoff,
+            ); 3],
+            median: [MedianFilter::new(params.hand_pos_filter_median_window as usize); 3],
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        for i in 0..3 {
+            self.ema[i].reset();
+            self.one_euro[i].reset();
+            self.median[i].reset();
+        }
+    }
+
+    /// Smoothes the position of a found hand. The filters are reset when the hand is not found.
+    pub(crate) fn smooth(&mut self, hand_state: HandState, time_ms: u32) -> HandState {
+        let HandState::HandFound {
+            hand_pos,
+            hand_features,
+        } = hand_state
+        else {
+            self.reset();
+            return hand_state;
+        };
+        let pos = CoordsCartesian::from(hand_pos);
+        let mut pos = [pos.x, pos.y, pos.z];
+
+        for (i, value) in pos.iter_mut().enumerate() {
+            *value = match self.kind {
+                HandPosFilter::HandPosFilterNone => *value,
+                HandPosFilter::HandPosFilterEma => self.ema[i].filter(*value),
+                HandPosFilter::HandPosFilterOneEuro => self.one_euro[i].filter(*value, time_ms),
+                HandPosFilter::HandPosFilterMedian => self.median[i].filter(*value),
+            };
+        }
+
+        HandState::HandFound {
+            hand_pos: CoordsSpherical::from(CoordsCartesian {
+                x: pos[0],
+                y: pos[1],
+                z: pos[2],
+            }),
+            hand_features,
+        }
+    }
+}
+
+/// The preprocessing and hand recognition of the measurements of a single sensor.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SensorPipeline<const RES_X: usize, const RES_Y: usize> {
+    pub(crate) sensor_params: SensorParams,
+    /// The zone geometry of the sensor parameters, for the per-zone computations.
+    geometry: ZoneGeometry<Scalar, RES_X, RES_Y>,
+    pub(crate) calibration: ZoneCalibration<RES_X, RES_Y>,
+    zone_filter: ZoneFilter<RES_X, RES_Y>,
+    pub(crate) background: BackgroundModel<RES_X, RES_Y>,
+    adaptive_threshold: AdaptiveThreshold,
+}
+
+impl<const RES_X: usize, const RES_Y: usize> SensorPipeline<RES_X, RES_Y> {
+    pub(crate) fn new(params: &RecognizerParams, sensor_params: SensorParams) -> Self {
+        Self {
+            geometry: ZoneGeometry::new(&sensor_params),
             sensor_params,
-            start_time: 0,
-            history: [HistoryEntry::invalid(); HISTORY_SIZE],
-            received_measurements: 0,
+            calibration: ZoneCalibration::identity(),
+            zone_filter: ZoneFilter::new(params.zone_filter, params.zone_filter_hysteresis_frames),
+            background: BackgroundModel::empty(),
+            adaptive_threshold: AdaptiveThreshold::new(params),
         }
     }
 
-    /// Resets the gesture recognizer with the given parameters.
+    /// Resets the pipeline with the given parameters. The zone calibration and the background are kept.
     ///
-    /// Clears the history, ongoing predictions and resets the internal state.
-    pub fn reset(
+    /// The sensor parameters must be validated before, see [SensorParams::is_orientation_supported].
+    pub(crate) fn reset(&mut self, params: &RecognizerParams, sensor_params: SensorParams) {
+        *self = Self {
+            calibration: self.calibration,
+            background: self.background,
+            ..Self::new(params, sensor_params)
+        };
+    }
+
+    /// The current furthest hand distance for gesture recognition.
+    pub(crate) fn gesture_threshold_dist(&self, params: &RecognizerParams) -> f32 {
+        match params.gesture_threshold_mode {
+            ThresholdMode::ThresholdModeFixed => params.gesture_threshold_dist,
+            ThresholdMode::ThresholdModeAdaptive => self.adaptive_threshold.threshold_dist(params),
+        }
+    }
+
+    /// Applies the mounting orientation, zone calibration, mask and the quality thresholds of the optional zone quality
+    /// to a measurement.
+    ///
+    /// Returns the tuple: ("corrected measurement", "oriented zone quality"),
+    /// or `None` if the mounting orientation is not supported for the resolution.
+    pub(crate) fn correct_measurement(
+        &self,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+    ) -> Option<(
+        SensorMeasurement<RES_X, RES_Y>,
+        Option<[[ZoneQuality; RES_X]; RES_Y]>,
+    )> {
+        let measurement = measurement.oriented(&self.sensor_params)?;
+        let measurement = self
+            .calibration
+            .apply(measurement)
+            .mask_zones(&self.sensor_params);
+
+        let Some(zone_quality) = zone_quality else {
+            return Some((measurement, None));
+        };
+        let zone_quality = measurements::zones_oriented(*zone_quality, &self.sensor_params)?;
+
+        Some((
+            measurement.reject_low_quality(&zone_quality, &self.sensor_params),
+            Some(zone_quality),
+        ))
+    }
+
+    /// Learns the static background from a measurement with no hand present.
+    pub(crate) fn calibrate_background(
         &mut self,
-        params: RecognizerParams,
-        sensor_params: SensorParams,
-        now: u32,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
     ) -> RecognizerStatus {
-        self.params = params;
-        self.sensor_params = sensor_params;
-        self.start_time = now;
-        self.clear_history();
+        let Some((measurement, _)) = self.correct_measurement(measurement, None) else {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+        self.background.learn(&measurement);
 
         RecognizerStatus::RecognizerStatusOk
     }
 
-    /// Updates the gesture recognizer with a new measurement and time.
+    /// Corrects the measurement and subtracts the background, without filtering or adapting it.
     ///
-    /// The time in the measurement must be monotonically increasing (usually coming from a systick timer).
-    pub fn update(
+    /// Used for the presence detection, so that masked zones and the static scene do not keep the presence.
+    /// Returns `None` if the mounting orientation is not supported for the resolution.
+    pub(crate) fn presence_measurement(
+        &self,
+        params: &RecognizerParams,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+    ) -> Option<SensorMeasurement<RES_X, RES_Y>> {
+        let (measurement, _) = self.correct_measurement(measurement, None)?;
+
+        Some(
+            self.background
+                .subtract(measurement, params.background_min_diff_dist),
+        )
+    }
+
+    /// Corrects and filters the measurement, subtracts the background and adapts it.
+    ///
+    /// Returns the tuple: ("measurement with subtracted background", "measurement before the subtraction",
+    /// "oriented zone quality"), or `None` if the mounting orientation is not supported for the resolution.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn preprocess(
         &mut self,
+        params: &RecognizerParams,
         measurement: SensorMeasurement<RES_X, RES_Y>,
-        result: &mut RecognizerResult,
-    ) -> RecognizerStatus {
-        *result = RecognizerResult::default();
-        let now = measurement.time_ms;
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+    ) -> Option<(
+        SensorMeasurement<RES_X, RES_Y>,
+        SensorMeasurement<RES_X, RES_Y>,
+        Option<[[ZoneQuality; RES_X]; RES_Y]>,
+    )> {
+        let (measurement, zone_quality) = self.correct_measurement(measurement, zone_quality)?;
+        let unsubtracted = self.zone_filter.filter(measurement);
+        let measurement = self
+            .background
+            .subtract(unsubtracted, params.background_min_diff_dist);
+        self.background.adapt(
+            &unsubtracted,
+            params.background_min_diff_dist,
+            params.background_adapt_rate,
+            params.background_persist_frames,
+        );
 
-        // Time must be monotonically increasing
-        if measurement.time_ms <= self.history.last().unwrap().measurement.time_ms {
-            return RecognizerStatus::RecognizerStatusInvalidInput;
+        Some((measurement, unsubtracted, zone_quality))
+    }
+
+    /// Recognizes the hand in the preprocessed measurement and updates the adaptive threshold.
+    ///
+    /// The hand position is converted into the user-facing frame of the sensor pose.
+    pub(crate) fn recognize_hand(
+        &mut self,
+        params: &RecognizerParams,
+        measurement: &SensorMeasurement<RES_X, RES_Y>,
+        unsubtracted: &SensorMeasurement<RES_X, RES_Y>,
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+    ) -> HandState {
+        let hand_state = measurement.recognize_hand(
+            &self.sensor_params,
+            &self.geometry,
+            params,
+            self.gesture_threshold_dist(params),
+            zone_quality,
+        );
+        self.adaptive_threshold
+            .update(params, unsubtracted, &self.background, hand_state);
+
+        match hand_state {
+            HandState::HandFound {
+                hand_pos,
+                hand_features,
+            } => HandState::HandFound {
+                hand_pos: self.sensor_params.to_user_frame(hand_pos),
+                hand_features,
+            },
+            HandState::HandNotFound => HandState::HandNotFound,
         }
+    }
+}
 
-        let hand_state =
-            measurement.recognize_hand(&self.sensor_params, self.params.gesture_threshold_dist);
-        self.push_to_history(HistoryEntry {
-            measurement,
-            hand_state,
-        });
-        result.hand_state = hand_state;
-        result.gesture = self.recognize_gesture(now);
+/// The number of measurements with a hand that are required in each half of the grab time.
+const GRAB_MIN_MEASUREMENTS: usize = 3;
 
-        RecognizerStatus::RecognizerStatusOk
+/// The history of the recognized hand states, from which the gestures are recognized.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub(crate) struct GestureHistory<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize>
+{
+    /// The entries, the most recent first.
+    entries: [HistoryEntry<RES_X, RES_Y>; HISTORY_SIZE],
+    received_measurements: usize,
+}
+
+impl<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize>
+    GestureHistory<RES_X, RES_Y, HISTORY_SIZE>
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: [HistoryEntry::invalid(); HISTORY_SIZE],
+            received_measurements: 0,
+        }
     }
 
-    /// Gets the current configured sensor parameters.
-    pub fn sensor_params(&self) -> SensorParams {
-        self.sensor_params
+    /// Checks if the time of a new measurement is valid in regards to the history.
+    pub(crate) fn is_valid_time(&self, time_ms: u32) -> bool {
+        // Time must be monotonically increasing
+        time_ms > self.entries.last().unwrap().time_ms
     }
 
-    /// Gets the current configured gesture recognizer parameters.
-    pub fn params(&self) -> RecognizerParams {
-        self.params
+    /// The index of the sensor of the most recent entry.
+    pub(crate) fn latest_sensor(&self) -> usize {
+        self.entries[0].sensor
     }
 
     /// Pushes an entry to the history.
-    fn push_to_history(&mut self, entry: HistoryEntry<RES_X, RES_Y>) {
-        self.history.rotate_right(1);
-        self.history[0] = entry;
+    pub(crate) fn push(&mut self, entry: HistoryEntry<RES_X, RES_Y>) {
+        self.entries.rotate_right(1);
+        self.entries[0] = entry;
         self.received_measurements += 1;
     }
 
     /// Clears the history and fills it with invalid measurements and state (all dist values set to -1.0).
-    fn clear_history(&mut self) {
-        for entry in self.history.iter_mut() {
+    pub(crate) fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
             *entry = HistoryEntry::invalid();
         }
 
         self.received_measurements = 0;
     }
 
-    /// Attempts to recognize a gesture from the measurements
-    fn recognize_gesture(&mut self, now: u32) -> Gesture {
+    /// Attempts to recognize a gesture from the history. The history is cleared when a gesture is recognized.
+    ///
+    /// `threshold_dists` are the current furthest hand distances for gesture recognition of every sensor.
+    pub(crate) fn recognize_gesture(
+        &mut self,
+        params: &RecognizerParams,
+        threshold_dists: &[f32],
+        now: u32,
+    ) -> Gesture {
         let mut gesture = Gesture::GestureNone;
 
-        if self.find_static_hold(now) {
+        let grab_gesture = self.find_grab(params);
+        if grab_gesture != Gesture::GestureNone {
+            gesture = grab_gesture;
+
+            trace!("recognized {} from the hand area", gesture);
+            self.clear();
+            return gesture;
+        }
+
+        let rotate_gesture = self.find_rotate(params);
+        if rotate_gesture != Gesture::GestureNone {
+            gesture = rotate_gesture;
+
+            trace!("recognized {} from the hand orientation", gesture);
+            self.clear();
+            return gesture;
+        }
+
+        if self.find_static_hold(params, threshold_dists, now) {
             gesture = Gesture::GestureStaticHold;
 
-            self.clear_history();
+            trace!("recognized {}", gesture);
+            self.clear();
             return gesture;
         }
 
-        let swipe_gesture = self.find_swipe(now);
+        let swipe_gesture = self.find_swipe(params, now);
         if swipe_gesture != Gesture::GestureNone {
             gesture = swipe_gesture;
 
-            self.clear_history();
+            trace!("recognized {}", gesture);
+            self.clear();
             return gesture;
         }
 
         gesture
     }
 
+    /// Tries to recognize a grab or release gesture.
+    ///
+    /// The median hand area of the newer half of the grab time is compared to the median hand area of the older half,
+    /// while the hand position must stay roughly constant.
+    /// Using the median of each half requires a consistent change, so a single noisy measurement does not trigger.
+    ///
+    /// Returns either GestureGrab / GestureRelease or GestureNone if no grab or release was found.
+    fn find_grab(&self, params: &RecognizerParams) -> Gesture {
+        let current = self.entries[0];
+        let HandState::HandFound { hand_pos, .. } = current.hand_state else {
+            return Gesture::GestureNone;
+        };
+        let hand_pos_cart = CoordsCartesian::from(hand_pos);
+        // The hand areas and their number of each half
+        let mut newer = ([0.0; HISTORY_SIZE], 0);
+        let mut older = ([0.0; HISTORY_SIZE], 0);
+
+        for e in iter_history_newer(self.entries, params.grab_time_ms, current.time_ms) {
+            if let HandState::HandFound {
+                hand_pos: hand_pos_older,
+                hand_features: hand_features_older,
+            } = e.hand_state
+            {
+                if hand_features_older.area <= 0.0
+                    || hand_pos_cart.dist_to(&hand_pos_older.into()) > params.grab_tolerance_dist
+                {
+                    continue;
+                }
+                let (areas, count) = if 2 * (current.time_ms - e.time_ms) < params.grab_time_ms {
+                    &mut newer
+                } else {
+                    &mut older
+                };
+                areas[*count] = hand_features_older.area;
+                *count += 1;
+            }
+        }
+        if newer.1 < GRAB_MIN_MEASUREMENTS || older.1 < GRAB_MIN_MEASUREMENTS {
+            return Gesture::GestureNone;
+        }
+        let area_ratio =
+            math::median(&mut newer.0[..newer.1]) / math::median(&mut older.0[..older.1]);
+
+        if area_ratio <= params.grab_area_ratio {
+            Gesture::GestureGrab
+        } else if area_ratio * params.grab_area_ratio >= 1.0 {
+            Gesture::GestureRelease
+        } else {
+            Gesture::GestureNone
+        }
+    }
+
+    /// Tries to recognize a rotate gesture.
+    ///
+    /// The orientation of the current hand is compared to the hand orientation of earlier measurements,
+    /// while the hand position must stay roughly constant.
+    ///
+    /// Returns either GestureRotateClockwise / GestureRotateCounterClockwise or GestureNone if no rotation was found.
+    fn find_rotate(&self, params: &RecognizerParams) -> Gesture {
+        let current = self.entries[0];
+        let HandState::HandFound {
+            hand_pos,
+            hand_features,
+        } = current.hand_state
+        else {
+            return Gesture::GestureNone;
+        };
+        if hand_features.elongation < params.rotate_min_elongation {
+            return Gesture::GestureNone;
+        }
+        let hand_pos_cart = CoordsCartesian::from(hand_pos);
+        let rotate_angle = params.rotate_angle.to_radians();
+
+        for e in iter_history_newer(self.entries, params.rotate_time_ms, current.time_ms) {
+            if let HandState::HandFound {
+                hand_pos: hand_pos_older,
+                hand_features: hand_features_older,
+            } = e.hand_state
+            {
+                if hand_features_older.elongation < params.rotate_min_elongation
+                    || hand_pos_cart.dist_to(&hand_pos_older.into()) > params.rotate_tolerance_dist
+                {
+                    continue;
+                }
+                // The orientation is an axis, so the difference wraps around at PI
+                let mut diff = hand_features.orientation - hand_features_older.orientation;
+                if diff > FRAC_PI_2 {
+                    diff -= PI;
+                } else if diff < -FRAC_PI_2 {
+                    diff += PI;
+                }
+
+                if diff >= rotate_angle {
+                    return Gesture::GestureRotateCounterClockwise;
+                }
+                if diff <= -rotate_angle {
+                    return Gesture::GestureRotateClockwise;
+                }
+            }
+        }
+
+        Gesture::GestureNone
+    }
+
     /// Attempts to recognize a static hold.
     ///
+    /// The nearest zone must stay within the tolerance in all measurements, all coming from the same sensor.
+    ///
     /// Returns true when a hold is recognized, else false.
-    fn find_static_hold(&self, now: u32) -> bool {
+    fn find_static_hold(
+        &self,
+        params: &RecognizerParams,
+        threshold_dists: &[f32],
+        now: u32,
+    ) -> bool {
         if self.received_measurements < HISTORY_SIZE.min(15) {
+            trace!(
+                "static hold rejected: only {} measurements received",
+                self.received_measurements
+            );
             return false;
         }
 
         let abs_min = measurements::find_nearest_zone(
-            iter_history_newer(self.history, self.params.static_hold_time_ms, now)
-                .map(|e| e.measurement),
+            iter_history_newer(self.entries, params.static_hold_time_ms, now).map(|e| e.zone_dist),
         );
+        let Some(sensor) = iter_history_newer(self.entries, params.static_hold_time_ms, now)
+            .nth(abs_min.0)
+            .map(|e| e.sensor)
+        else {
+            return false;
+        };
+
+        let threshold_dist = threshold_dists[sensor];
 
-        if abs_min.2 <= 0.0 || abs_min.2 > self.params.gesture_threshold_dist {
+        if abs_min.2 <= 0.0 || abs_min.2 > threshold_dist {
+            trace!(
+                "static hold rejected: nearest zone at {} mm, threshold {} mm",
+                abs_min.2,
+                threshold_dist
+            );
             return false;
         }
 
         // Only returns true if all measurements meet the condition.
-        !iter_history_newer(self.history, self.params.static_hold_time_ms, now).any(|e| {
-            let zone_dist = e.measurement.zone_dist[abs_min.1[0]][abs_min.1[1]];
+        let hold = !iter_history_newer(self.entries, params.static_hold_time_ms, now).any(|e| {
+            if e.sensor != sensor {
+                trace!(
+                    "static hold rejected: nearest zone changed from sensor {} to {}",
+                    sensor,
+                    e.sensor
+                );
+                return true;
+            }
+            let zone_dist = e.zone_dist[abs_min.1[0]][abs_min.1[1]];
 
-            if zone_dist <= 0.0 || zone_dist > self.params.gesture_threshold_dist {
+            if zone_dist <= 0.0 || zone_dist > threshold_dist {
+                trace!(
+                    "static hold rejected: zone at {} mm left the threshold {} mm",
+                    zone_dist,
+                    threshold_dist
+                );
                 return true;
             }
 
-            zone_dist > abs_min.2 + self.params.static_hold_tolerance_dist
-                || zone_dist < abs_min.2 - self.params.static_hold_tolerance_dist
-        })
+            let moved = zone_dist > abs_min.2 + params.static_hold_tolerance_dist
+                || zone_dist < abs_min.2 - params.static_hold_tolerance_dist;
+            if moved {
+                trace!(
+                    "static hold rejected: zone at {} mm moved from the nearest {} mm beyond the tolerance",
+                    zone_dist,
+                    abs_min.2
+                );
+            }
+
+            moved
+        });
+
+        if hold {
+            trace!("static hold accepted: nearest zone at {} mm", abs_min.2);
+        }
+
+        hold
     }
 
     /// Tries to recognize a swipe gesture.
     ///
     /// Returns either GestureSwipeRight / GestureSwipeLeft or GestureNone if no swipe was found.
-    fn find_swipe(&self, now: u32) -> Gesture {
+    fn find_swipe(&self, params: &RecognizerParams, now: u32) -> Gesture {
         if self.received_measurements < HISTORY_SIZE.min(15) {
+            trace!(
+                "swipe rejected: only {} measurements received",
+                self.received_measurements
+            );
             return Gesture::GestureNone;
         }
+        // The candidates rejected because of the depth, and the largest travel of the others
+        #[cfg(feature = "defmt")]
+        let (mut depth_rejected, mut max_travel_hor, mut max_travel_vert) =
+            (0_u32, 0.0_f32, 0.0_f32);
+
+        // The cartesian hand positions of the newer entries, converted once instead of for every older entry
+        let mut newer = [CoordsCartesian::zero(); HISTORY_SIZE];
+        let mut newer_len = 0;
+        for n in iter_history_newer(self.entries, 300, now) {
+            if let HandState::HandFound { hand_pos, .. } = n.hand_state {
+                newer[newer_len] = CoordsCartesian::from(hand_pos);
+                newer_len += 1;
+            }
+        }
+
+        for e in iter_history_newer(iter_history_older_eq(self.entries, 300, now), 600, now) {
+            if let HandState::HandFound { hand_pos, .. } = e.hand_state {
+                let hand_pos_cart = CoordsCartesian::from(hand_pos);
 
-        for e in iter_history_newer(iter_history_older_eq(self.history, 300, now), 600, now) {
-            if let HandState::HandFound { hand_pos } = e.hand_state {
-                for n in iter_history_newer(self.history, 300, now) {
-                    // Preconditions for a detected swipe in any direction
-                    if let HandState::HandFound {
-                        hand_pos: hand_pos_newer,
-                    } = n.hand_state
+                // Preconditions for a detected swipe in any direction
+                for hand_pos_newer_cart in &newer[..newer_len] {
+                    if (hand_pos_newer_cart.x >= hand_pos_cart.x - params.swipe_tolerance_dist)
+                        && (hand_pos_newer_cart.x < hand_pos_cart.x + params.swipe_tolerance_dist)
                     {
-                        let hand_pos_cart = CoordsCartesian::from(hand_pos);
-                        let hand_pos_newer_cart = CoordsCartesian::from(hand_pos_newer);
+                        let travel_hor = hand_pos_newer_cart.y - hand_pos_cart.y;
+                        let travel_vert = hand_pos_newer_cart.z - hand_pos_cart.z;
+                        #[cfg(feature = "defmt")]
+                        {
+                            max_travel_hor = max_travel_hor.max(libm::fabsf(travel_hor));
+                            max_travel_vert = max_travel_vert.max(libm::fabsf(travel_vert));
+                        }
+
+                        // Detect right swipe
+                        if travel_hor > params.swipe_horizontal_travel_dist {
+                            trace!("swipe accepted: {} mm to the right", travel_hor);
+                            return Gesture::GestureSwipeRight;
+                        }
+
+                        // Detect left swipe
+                        if travel_hor < -params.swipe_horizontal_travel_dist {
+                            trace!("swipe accepted: {} mm to the left", -travel_hor);
+                            return Gesture::GestureSwipeLeft;
+                        }
+
+                        // Detect up swipe
+                        if travel_vert > params.swipe_vertical_travel_dist {
+                            trace!("swipe accepted: {} mm upwards", travel_vert);
+                            return Gesture::GestureSwipeUp;
+                        }
 
-                        if (hand_pos_newer_cart.x
-                            >= hand_pos_cart.x - self.params.swipe_tolerance_dist)
-                            && (hand_pos_newer_cart.x
-                                < hand_pos_cart.x + self.params.swipe_tolerance_dist)
+                        // Detect down swipe
+                        if travel_vert < -params.swipe_vertical_travel_dist {
+                            trace!("swipe accepted: {} mm downwards", -travel_vert);
+                            return Gesture::GestureSwipeDown;
+                        }
+                    } else {
+                        #[cfg(feature = "defmt")]
                         {
-                            // Detect right swipe
-                            if hand_pos_newer_cart.y - hand_pos_cart.y
-                                > self.params.swipe_horizontal_travel_dist
-                            {
-                                return Gesture::GestureSwipeRight;
-                            }
-
-                            // Detect left swipe
-                            if hand_pos_newer_cart.y - hand_pos_cart.y
-                                < -self.params.swipe_horizontal_travel_dist
-                            {
-                                return Gesture::GestureSwipeLeft;
-                            }
-
-                            // Detect up swipe
-                            if hand_pos_newer_cart.z - hand_pos_cart.z
-                                > self.params.swipe_vertical_travel_dist
-                            {
-                                return Gesture::GestureSwipeUp;
-                            }
-
-                            // Detect down swipe
-                            if hand_pos_newer_cart.z - hand_pos_cart.z
-                                < -self.params.swipe_vertical_travel_dist
-                            {
-                                return Gesture::GestureSwipeDown;
-                            }
+                            depth_rejected += 1;
                         }
                     }
                 }
             }
         }
 
+        trace!(
+            "swipe rejected: {} candidates beyond the depth tolerance, largest travel {} mm horizontal, {} mm vertical",
+            depth_rejected,
+            max_travel_hor,
+            max_travel_vert
+        );
         Gesture::GestureNone
     }
 }
 
+/// The gesture recognizer.
+///
+/// Is initially configured through parameters and gets fed measurements and time and predicts gestures.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize> {
+    params: RecognizerParams,
+    start_time: u32,
+    pipeline: SensorPipeline<RES_X, RES_Y>,
+    history: GestureHistory<RES_X, RES_Y, HISTORY_SIZE>,
+    presence: PresenceDetector,
+    smoother: HandPosSmoother,
+    tracker: HandTracker,
+}
+
+impl<const RES_X: usize, const RES_Y: usize, const HISTORY_SIZE: usize>
+    GestureRecognizer<RES_X, RES_Y, HISTORY_SIZE>
+{
+    /// A new gesture recognizer initialized with the given parameters.
+    ///
+    /// The sensor parameters have preconfigured defaults for common TOF-Sensors such as the ST VL53L5CX.
+    ///
+    /// Returns [RecognizerError::InitFailure] if the mounting orientation is not supported for the resolution,
+    /// see [SensorParams::is_orientation_supported].
+    pub fn new(
+        params: RecognizerParams,
+        sensor_params: SensorParams,
+    ) -> Result<Self, RecognizerError> {
+        if !sensor_params.is_orientation_supported::<RES_X, RES_Y>() {
+            return Err(RecognizerError::InitFailure);
+        }
+
+        Ok(Self {
+            params,
+            start_time: 0,
+            pipeline: SensorPipeline::new(&params, sensor_params),
+            history: GestureHistory::new(),
+            presence: new_presence_detector(&params),
+            smoother: HandPosSmoother::new(&params),
+            tracker: new_tracker(&params),
+        })
+    }
+
+    /// Resets the gesture recognizer with the given parameters.
+    ///
+    /// Clears the history, ongoing predictions and resets the internal state,
+    /// including the adaptive threshold, the zone filter and the presence detection.
+    /// The zone calibration and the calibrated background are kept.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInitFailure] and keeps the current parameters and state
+    /// if the mounting orientation is not supported for the resolution, see [SensorParams::is_orientation_supported].
+    pub fn reset(
+        &mut self,
+        params: RecognizerParams,
+        sensor_params: SensorParams,
+        now: u32,
+    ) -> RecognizerStatus {
+        if !sensor_params.is_orientation_supported::<RES_X, RES_Y>() {
+            return RecognizerStatus::RecognizerStatusInitFailure;
+        }
+
+        self.params = params;
+        self.start_time = now;
+        self.history.clear();
+        self.presence = new_presence_detector(&params);
+        self.smoother = HandPosSmoother::new(&params);
+        self.tracker = new_tracker(&params);
+        self.pipeline.reset(&params, sensor_params);
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Updates the gesture recognizer with a new measurement and time.
+    ///
+    /// The time in the measurement must be monotonically increasing (usually coming from a systick timer).
+    /// The zones are oriented according to the mounting orientation of the sensor parameters.
+    pub fn update(
+        &mut self,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        self.update_impl(measurement, None, result)
+    }
+
+    /// Updates the gesture recognizer with a new measurement and the quality of its zones, see [Self::update].
+    ///
+    /// The zone quality is expected in the same order as the zones of the measurement.
+    /// Zones that are not accepted by the quality thresholds of the sensor parameters are ignored,
+    /// the others are weighted with their confidence.
+    pub fn update_with_quality(
+        &mut self,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+        zone_quality: &[[ZoneQuality; RES_X]; RES_Y],
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        self.update_impl(measurement, Some(zone_quality), result)
+    }
+
+    fn update_impl(
+        &mut self,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+        zone_quality: Option<&[[ZoneQuality; RES_X]; RES_Y]>,
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        *result = RecognizerResult::default();
+        let now = measurement.time_ms;
+
+        if !self.history.is_valid_time(now) {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        }
+
+        if self.params.presence_mode {
+            // Checked before the filtering and the recognition, so they do not run while nothing is present
+            let Some(presence_measurement) = self
+                .pipeline
+                .presence_measurement(&self.params, measurement)
+            else {
+                return RecognizerStatus::RecognizerStatusInvalidInput;
+            };
+            result.presence_event = self.presence.update(&presence_measurement);
+
+            if result.presence_event == PresenceEvent::PresenceLeft {
+                self.history.clear();
+                self.smoother.reset();
+                self.tracker.reset();
+                self.pipeline.zone_filter.reset();
+            }
+            if !self.presence.is_present() {
+                return RecognizerStatus::RecognizerStatusOk;
+            }
+        }
+
+        let Some((measurement, unsubtracted, zone_quality)) =
+            self.pipeline
+                .preprocess(&self.params, measurement, zone_quality)
+        else {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+
+        let hand_state = self.pipeline.recognize_hand(
+            &self.params,
+            &measurement,
+            &unsubtracted,
+            zone_quality.as_ref(),
+        );
+        let hand_state = self.smoother.smooth(hand_state, now);
+        let hand_state = track_hand(&mut self.tracker, &self.params, hand_state, now);
+        self.history.push(HistoryEntry {
+            time_ms: now,
+            sensor: 0,
+            zone_dist: measurement.zone_dist,
+            hand_state,
+        });
+        result.hand_state = hand_state;
+        result.gesture =
+            self.history
+                .recognize_gesture(&self.params, &[self.gesture_threshold_dist()], now);
+        if result.gesture != Gesture::GestureNone {
+            // The history was cleared, so the hand must not be predicted into the next gesture
+            self.tracker.reset();
+        }
+
+        RecognizerStatus::RecognizerStatusOk
+    }
+
+    /// Updates the gesture recognizer with a new measurement with multiple targets per zone.
+    ///
+    /// The nearest valid target of every zone is used for recognition, see [MultiTargetMeasurement::nearest_targets].
+    /// Further targets are used where the nearer ones are skipped, e.g. reflections of a cover
+    /// that are closer than the minimum target distance of the sensor parameters.
+    pub fn update_multi_target<const TARGETS: usize>(
+        &mut self,
+        measurement: MultiTargetMeasurement<RES_X, RES_Y, TARGETS>,
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        let (measurement, zone_quality) = measurement.nearest_targets(&self.pipeline.sensor_params);
+
+        self.update_with_quality(measurement, &zone_quality, result)
+    }
+
+    /// Gets the current configured sensor parameters.
+    pub fn sensor_params(&self) -> SensorParams {
+        self.pipeline.sensor_params
+    }
+
+    /// Gets the current configured gesture recognizer parameters.
+    pub fn params(&self) -> RecognizerParams {
+        self.params
+    }
+
+    /// Gets the current furthest hand distance for gesture recognition.
+    ///
+    /// Either the configured fixed distance or the current adaptive distance, depending on the threshold mode.
+    pub fn gesture_threshold_dist(&self) -> f32 {
+        self.pipeline.gesture_threshold_dist(&self.params)
+    }
+
+    /// Gets the per-zone range calibration.
+    pub fn calibration(&self) -> ZoneCalibration<RES_X, RES_Y> {
+        self.pipeline.calibration
+    }
+
+    /// Sets the per-zone range calibration, that is applied to every measurement before the hand is recognized.
+    ///
+    /// The calibration can be computed with a [crate::calibration::ZoneCalibrator].
+    pub fn set_calibration(&mut self, calibration: ZoneCalibration<RES_X, RES_Y>) {
+        self.pipeline.calibration = calibration;
+    }
+
+    /// Learns the static background from a measurement with no hand present.
+    ///
+    /// Should be called with several frames. Afterwards only zones that are significantly closer
+    /// than the background count towards hand recognition.
+    pub fn calibrate_background(
+        &mut self,
+        measurement: SensorMeasurement<RES_X, RES_Y>,
+    ) -> RecognizerStatus {
+        self.pipeline.calibrate_background(measurement)
+    }
+
+    /// Gets the calibrated background, e.g. to store it.
+    pub fn background(&self) -> BackgroundModel<RES_X, RES_Y> {
+        self.pipeline.background
+    }
+
+    /// Sets the background, e.g. a previously stored one.
+    pub fn set_background(&mut self, background: BackgroundModel<RES_X, RES_Y>) {
+        self.pipeline.background = background;
+    }
+
+    /// Clears the background, all zones then count towards hand recognition again.
+    pub fn clear_background(&mut self) {
+        self.pipeline.background = BackgroundModel::empty();
+    }
+
+    /// Gets the hand tracker, providing the estimated hand position and velocity.
+    ///
+    /// Only updated when the tracker is enabled in the parameters.
+    pub fn tracker(&self) -> &HandTracker {
+        &self.tracker
+    }
+
+    /// Whether something is currently present in presence mode.
+    pub fn is_present(&self) -> bool {
+        self.presence.is_present()
+    }
+}
+
+fn new_presence_detector(params: &RecognizerParams) -> PresenceDetector {
+    PresenceDetector::new(
+        params.presence_threshold_dist,
+        params.presence_enter_frames,
+        params.presence_leave_frames,
+    )
+}
+
+pub(crate) fn new_tracker(params: &RecognizerParams) -> HandTracker {
+    HandTracker::new(
+        params.tracker_accel_noise,
+        params.tracker_measurement_noise,
+        params.tracker_max_dropout_ms,
+    )
+}
+
+/// Updates the tracker with the hand state if it is enabled, else passes the hand state through.
+pub(crate) fn track_hand(
+    tracker: &mut HandTracker,
+    params: &RecognizerParams,
+    hand_state: HandState,
+    now: u32,
+) -> HandState {
+    if !params.tracker_enabled {
+        return hand_state;
+    }
+
+    tracker.update(hand_state, now)
+}
+
 pub(crate) fn iter_history_newer<
     const RES_X: usize,
     const RES_Y: usize,
@@ -328,7 +1336,7 @@ pub(crate) fn iter_history_newer<
 ) -> impl Iterator<Item = HistoryEntry<RES_X, RES_Y>> {
     entries
         .into_iter()
-        .filter(move |e| now - e.measurement.time_ms < newer_than_ms)
+        .filter(move |e| now - e.time_ms < newer_than_ms)
 }
 
 pub(crate) fn iter_history_older_eq<
@@ -342,5 +1350,460 @@ pub(crate) fn iter_history_older_eq<
 ) -> impl Iterator<Item = HistoryEntry<RES_X, RES_Y>> {
     entries
         .into_iter()
-        .filter(move |e| now - e.measurement.time_ms >= older_eq_ms)
+        .filter(move |e| now - e.time_ms >= older_eq_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Gesture, GestureRecognizer, RecognizerError, RecognizerParams, RecognizerResult,
+        RecognizerStatus, ThresholdMode,
+    };
+    use crate::measurements::{MountingRotation, SensorParams};
+    use crate::preprocessing::ZoneFilterMode;
+    use crate::presence::PresenceEvent;
+    use crate::{HandState, MultiTargetMeasurement, SensorMeasurement};
+    use approx::assert_relative_eq;
+
+    /// A hand at the given distance, that is seen by the zones of two columns starting at the given column.
+    fn hand_at_column(column: Option<usize>, dist: f32, time_ms: u32) -> SensorMeasurement<8, 8> {
+        let mut measurement = SensorMeasurement::<8, 8>::invalid();
+        measurement.time_ms = time_ms;
+
+        if let Some(column) = column {
+            for row in measurement.zone_dist[2..6].iter_mut() {
+                row[column] = dist;
+                row[column + 1] = dist;
+            }
+        }
+
+        measurement
+    }
+
+    /// A hand at 250mm, that is seen by the zones (x, y) for which `is_hand` is true.
+    fn hand_where(is_hand: impl Fn(usize, usize) -> bool, time_ms: u32) -> SensorMeasurement<8, 8> {
+        let mut measurement = SensorMeasurement::<8, 8>::invalid();
+        measurement.time_ms = time_ms;
+
+        for (y, row) in measurement.zone_dist.iter_mut().enumerate() {
+            for (x, dist) in row.iter_mut().enumerate() {
+                if is_hand(x, y) {
+                    *dist = 250.0;
+                }
+            }
+        }
+
+        measurement
+    }
+
+    /// An open palm covering the center of the FOV.
+    fn palm(x: usize, y: usize) -> bool {
+        (2..6).contains(&x) && (1..7).contains(&y)
+    }
+
+    /// A fist in the center of the FOV.
+    fn fist(x: usize, y: usize) -> bool {
+        (3..5).contains(&x) && (3..5).contains(&y)
+    }
+
+    /// A flat hand or arm through the center of the FOV, rotated counterclockwise from the horizontal axis.
+    fn bar(angle: f32) -> impl Fn(usize, usize) -> bool {
+        let (sin, cos) = (angle.to_radians().sin(), angle.to_radians().cos());
+
+        move |x, y| {
+            // The zone center relative to the FOV center, in zones to the right and upwards
+            let (right, up) = (x as f32 + 0.5 - 4.0, y as f32 + 0.5 - 4.0);
+
+            (cos * up - sin * right).abs() < 0.75 && (cos * right + sin * up).abs() < 3.5
+        }
+    }
+
+    /// Feeds the measurements to the recognizer every 33ms and collects the recognized gestures.
+    fn recognize(
+        recognizer: &mut GestureRecognizer<8, 8, 60>,
+        measurements: impl IntoIterator<Item = SensorMeasurement<8, 8>>,
+    ) -> Vec<Gesture> {
+        let mut result = RecognizerResult::default();
+
+        measurements
+            .into_iter()
+            .filter_map(|measurement| {
+                recognizer.update(measurement, &mut result);
+                (result.gesture != Gesture::GestureNone).then_some(result.gesture)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tracker_no_swipe_from_dropout() {
+        let params = RecognizerParams {
+            tracker_enabled: true,
+            tracker_max_dropout_ms: 1000,
+            ..Default::default()
+        };
+        let mut recognizer =
+            GestureRecognizer::<8, 8, 60>::new(params, SensorParams::default_vl53l5cx()).unwrap();
+        // No hand, then a hand swiping to the right that leaves the FOV quickly
+        let columns = (0..20)
+            .map(|_| None)
+            .chain((0..10).map(|_| Some(0)))
+            .chain((0..7).map(Some))
+            .chain((0..40).map(|_| None));
+
+        let gestures = recognize(
+            &mut recognizer,
+            columns
+                .enumerate()
+                .map(|(i, column)| hand_at_column(column, 200.0, 10 + i as u32 * 33)),
+        );
+
+        // The tracker would predict the hand further to the right after the swipe
+        assert_eq!(gestures, [Gesture::GestureSwipeRight]);
+    }
+
+    #[test]
+    fn swipe_direction_for_every_mounting() {
+        let rotations = [
+            MountingRotation::MountingRotation0,
+            MountingRotation::MountingRotation90,
+            MountingRotation::MountingRotation180,
+            MountingRotation::MountingRotation270,
+        ];
+        // A hand crossing the FOV to the right, or upwards
+        let is_hand = |swipe: Gesture, pos: usize, x: usize, y: usize| {
+            let (along, across) = if swipe == Gesture::GestureSwipeRight {
+                (x, y)
+            } else {
+                (y, x)
+            };
+            (pos..pos + 2).contains(&along) && (2..6).contains(&across)
+        };
+        for rotation in rotations {
+            for mirror_horizontal in [false, true] {
+                for mirror_vertical in [false, true] {
+                    let mut sensor_params = SensorParams::default_vl53l5cx();
+                    sensor_params.mounting_rotation = rotation;
+                    sensor_params.mounting_mirror_horizontal = mirror_horizontal;
+                    sensor_params.mounting_mirror_vertical = mirror_vertical;
+
+                    // Where the zone (x, y) of the sensor ends up after the documented clockwise rotation,
+                    // the horizontal mirror and then the vertical mirror
+                    let oriented = |x: usize, y: usize| {
+                        let (x, y) = (0..rotation as usize).fold((x, y), |(x, y), _| (y, 7 - x));
+                        let x = if mirror_horizontal { 7 - x } else { x };
+                        let y = if mirror_vertical { 7 - y } else { y };
+                        (x, y)
+                    };
+
+                    for expected in [Gesture::GestureSwipeRight, Gesture::GestureSwipeUp] {
+                        let mut recognizer = GestureRecognizer::<8, 8, 60>::new(
+                            RecognizerParams::default(),
+                            sensor_params,
+                        )
+                        .unwrap();
+                        let positions = (0..20)
+                            .map(|_| None)
+                            .chain((0..7).map(Some))
+                            .chain((0..40).map(|_| None));
+
+                        let gestures = recognize(
+                            &mut recognizer,
+                            positions.enumerate().map(|(i, pos)| {
+                                hand_where(
+                                    |x, y| {
+                                        let (x, y) = oriented(x, y);
+                                        pos.is_some_and(|pos| is_hand(expected, pos, x, y))
+                                    },
+                                    10 + i as u32 * 33,
+                                )
+                            }),
+                        );
+
+                        assert_eq!(
+                            gestures,
+                            [expected],
+                            "{rotation:?}, mirror horizontal {mirror_horizontal}, mirror vertical {mirror_vertical}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_threshold_no_collapse() {
+        let params = RecognizerParams {
+            gesture_threshold_mode: ThresholdMode::ThresholdModeAdaptive,
+            ..Default::default()
+        };
+        let mut recognizer =
+            GestureRecognizer::<8, 8, 60>::new(params, SensorParams::default_vl53l5cx()).unwrap();
+        let mut result = RecognizerResult::default();
+        assert_relative_eq!(recognizer.gesture_threshold_dist(), 800.0);
+
+        // Converges in front of a wall without a calibrated background
+        for i in 0..1000 {
+            let mut wall = SensorMeasurement::new([[900.0; 8]; 8]);
+            wall.time_ms = 10 + i * 33;
+            recognizer.update(wall, &mut result);
+        }
+        assert_relative_eq!(recognizer.gesture_threshold_dist(), 750.0, epsilon = 1.0);
+
+        // Narrows with a hand in front of the wall, but stays behind it
+        for i in 1000..2000 {
+            let mut hand = SensorMeasurement::new([[900.0; 8]; 8]);
+            hand.time_ms = 10 + i * 33;
+            for row in hand.zone_dist[2..6].iter_mut() {
+                row[3..5].fill(250.0);
+            }
+            recognizer.update(hand, &mut result);
+        }
+        let HandState::HandFound { hand_pos, .. } = result.hand_state else {
+            panic!("hand should still be found");
+        };
+        assert!(hand_pos.r < 500.0);
+        assert_relative_eq!(
+            recognizer.gesture_threshold_dist(),
+            hand_pos.r + 200.0,
+            epsilon = 1.0
+        );
+    }
+
+    #[test]
+    fn multi_target_through_cover() {
+        let mut sensor_params = SensorParams::default_vl53l5cx();
+        sensor_params.min_target_dist = 50.0;
+        let mut recognizer =
+            GestureRecognizer::<8, 8, 60>::new(RecognizerParams::default(), sensor_params).unwrap();
+        let mut result = RecognizerResult::default();
+        let mut measurement = MultiTargetMeasurement::<8, 8, 2>::invalid();
+        measurement.time_ms = 10;
+        for row in measurement.zone_targets.iter_mut() {
+            for targets in row.iter_mut() {
+                targets[0].dist = 20.0;
+            }
+        }
+        for row in measurement.zone_targets[3..5].iter_mut() {
+            for targets in row[3..5].iter_mut() {
+                targets[1].dist = 250.0;
+            }
+        }
+
+        recognizer.update_multi_target(measurement, &mut result);
+        let HandState::HandFound { hand_pos, .. } = result.hand_state else {
+            panic!("hand behind the cover should be found");
+        };
+        assert_relative_eq!(hand_pos.r, 250.0, epsilon = 10.0);
+    }
+
+    #[test]
+    fn grab_and_release() {
+        let mut recognizer = GestureRecognizer::<8, 8, 60>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        // An open palm that closes to a fist and opens again, at 15 Hz
+        let poses = (0..30).map(|i| {
+            let pose = if (10..20).contains(&i) { fist } else { palm };
+            hand_where(pose, 1000 + i * 66)
+        });
+
+        assert_eq!(
+            recognize(&mut recognizer, poses),
+            [Gesture::GestureGrab, Gesture::GestureRelease]
+        );
+    }
+
+    #[test]
+    fn grab_not_from_noise() {
+        let mut recognizer = GestureRecognizer::<8, 8, 60>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        // An open palm, of which single measurements only see a part
+        let poses = (0..20).map(|i| {
+            let pose = if i % 6 == 5 { fist } else { palm };
+            hand_where(pose, 1000 + i * 66)
+        });
+
+        let gestures = recognize(&mut recognizer, poses);
+        assert!(!gestures.contains(&Gesture::GestureGrab));
+        assert!(!gestures.contains(&Gesture::GestureRelease));
+    }
+
+    #[test]
+    fn rotate_direction() {
+        let rotate = |angles: &[f32]| {
+            let mut recognizer = GestureRecognizer::<8, 8, 60>::new(
+                RecognizerParams::default(),
+                SensorParams::default_vl53l5cx(),
+            )
+            .unwrap();
+            let measurements = angles
+                .iter()
+                .enumerate()
+                .map(|(i, &angle)| hand_where(bar(angle), 1000 + i as u32 * 66));
+
+            recognize(&mut recognizer, measurements)
+        };
+
+        assert_eq!(
+            rotate(&[-30.0, -20.0, -10.0, 0.0, 10.0, 20.0, 30.0]),
+            [Gesture::GestureRotateCounterClockwise]
+        );
+        assert_eq!(
+            rotate(&[30.0, 20.0, 10.0, 0.0, -10.0, -20.0, -30.0]),
+            [Gesture::GestureRotateClockwise]
+        );
+        // Through the vertical axis, where the orientation wraps around from PI/2 to -PI/2
+        assert_eq!(
+            rotate(&[60.0, 70.0, 80.0, 90.0, 100.0, 110.0, 120.0]),
+            [Gesture::GestureRotateCounterClockwise]
+        );
+        assert_eq!(
+            rotate(&[120.0, 110.0, 100.0, 90.0, 80.0, 70.0, 60.0]),
+            [Gesture::GestureRotateClockwise]
+        );
+        // Too small rotations
+        assert_eq!(rotate(&[-10.0, -5.0, 0.0, 5.0, 10.0, 15.0, 20.0]), []);
+        assert_eq!(rotate(&[80.0, 85.0, 90.0, 95.0, 100.0, 105.0, 110.0]), []);
+    }
+
+    #[test]
+    fn presence_mode_skips_recognition() {
+        let params = RecognizerParams {
+            presence_mode: true,
+            presence_threshold_dist: 200.0,
+            zone_filter: ZoneFilterMode::ZoneFilterMedian3,
+            ..Default::default()
+        };
+        let mut recognizer =
+            GestureRecognizer::<8, 8, 60>::new(params, SensorParams::default_vl53l5cx()).unwrap();
+        let mut result = RecognizerResult::default();
+
+        // A hand within the gesture threshold, but beyond the presence threshold
+        for i in 0..10 {
+            recognizer.update(hand_at_column(Some(3), 300.0, 1000 + i * 33), &mut result);
+
+            assert!(!recognizer.is_present());
+            assert!(matches!(result.hand_state, HandState::HandNotFound));
+        }
+        assert_eq!(recognizer.history.received_measurements, 0);
+
+        // The hand approaches, presence is entered from the unfiltered measurements
+        recognizer.update(hand_at_column(Some(3), 150.0, 2000), &mut result);
+        assert_eq!(result.presence_event, PresenceEvent::PresenceNone);
+        recognizer.update(hand_at_column(Some(3), 150.0, 2033), &mut result);
+        assert_eq!(result.presence_event, PresenceEvent::PresenceEntered);
+        // The zone filter only starts with the first present measurement
+        assert!(matches!(result.hand_state, HandState::HandNotFound));
+        recognizer.update(hand_at_column(Some(3), 150.0, 2066), &mut result);
+        assert!(matches!(result.hand_state, HandState::HandFound { .. }));
+        assert_eq!(recognizer.history.received_measurements, 2);
+    }
+
+    #[test]
+    fn presence_ignores_masked_zones_and_background() {
+        let params = RecognizerParams {
+            presence_mode: true,
+            presence_threshold_dist: 200.0,
+            ..Default::default()
+        };
+        let mut sensor_params = SensorParams::default_vl53l5cx();
+        sensor_params.mask_zone(0, 0);
+        let mut recognizer = GestureRecognizer::<8, 8, 60>::new(params, sensor_params).unwrap();
+        let mut result = RecognizerResult::default();
+        // A near obstacle in the masked zone and a shelf in the calibrated background
+        let scene = |hand_dist: Option<f32>, time_ms| {
+            let mut measurement =
+                hand_at_column(hand_dist.map(|_| 3), hand_dist.unwrap_or(0.0), time_ms);
+            measurement.zone_dist[0][0] = 50.0;
+            measurement.zone_dist[7].fill(150.0);
+            measurement
+        };
+        recognizer.calibrate_background(scene(None, 0));
+
+        for i in 0..10 {
+            recognizer.update(scene(None, 1000 + i * 33), &mut result);
+            assert!(!recognizer.is_present());
+        }
+
+        // A hand in front of them is still detected
+        recognizer.update(scene(Some(100.0), 2000), &mut result);
+        recognizer.update(scene(Some(100.0), 2033), &mut result);
+        assert_eq!(result.presence_event, PresenceEvent::PresenceEntered);
+    }
+
+    #[test]
+    fn reset_unsupported_orientation() {
+        let mut recognizer = GestureRecognizer::<8, 4, 60>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        let params = RecognizerParams {
+            gesture_threshold_dist: 300.0,
+            ..Default::default()
+        };
+        let mut sensor_params = SensorParams::default_vl53l5cx();
+        sensor_params.mounting_rotation = MountingRotation::MountingRotation90;
+
+        assert!(matches!(
+            recognizer.reset(params, sensor_params, 0),
+            RecognizerStatus::RecognizerStatusInitFailure
+        ));
+        assert_eq!(recognizer.params().gesture_threshold_dist, 400.0);
+        assert_eq!(
+            recognizer.sensor_params().mounting_rotation,
+            MountingRotation::MountingRotation0
+        );
+
+        sensor_params.mounting_rotation = MountingRotation::MountingRotation180;
+        assert!(matches!(
+            recognizer.reset(params, sensor_params, 0),
+            RecognizerStatus::RecognizerStatusOk
+        ));
+        assert_eq!(recognizer.params().gesture_threshold_dist, 300.0);
+    }
+
+    #[test]
+    fn new_unsupported_orientation() {
+        let mut sensor_params = SensorParams::default_vl53l5cx();
+        sensor_params.mounting_rotation = MountingRotation::MountingRotation270;
+
+        assert!(matches!(
+            GestureRecognizer::<8, 4, 60>::new(RecognizerParams::default(), sensor_params),
+            Err(RecognizerError::InitFailure)
+        ));
+    }
+
+    #[test]
+    fn status_into_result() {
+        let mut recognizer = GestureRecognizer::<8, 4, 60>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        let mut sensor_params = SensorParams::default_vl53l5cx();
+
+        assert_eq!(
+            recognizer
+                .reset(RecognizerParams::default(), sensor_params, 0)
+                .into_result(),
+            Ok(())
+        );
+        sensor_params.mounting_rotation = MountingRotation::MountingRotation90;
+        assert_eq!(
+            recognizer
+                .reset(RecognizerParams::default(), sensor_params, 0)
+                .into_result(),
+            Err(RecognizerError::InitFailure)
+        );
+        assert_eq!(
+            RecognizerStatus::RecognizerStatusInvalidInput.into_result(),
+            Err(RecognizerError::InvalidInput)
+        );
+    }
 }
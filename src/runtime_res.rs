@@ -0,0 +1,294 @@
+//! Runtime-selectable Resolution.
+//!
+//! All distance values are considered to be in millimeter.
+
+use crate::measurements::SensorParams;
+use crate::{
+    GestureRecognizer, RecognizerError, RecognizerParams, RecognizerResult, RecognizerStatus,
+    SensorMeasurement, ZoneQuality,
+};
+
+/// A gesture recognizer with a resolution that is selected at runtime, up to the compile-time maximum resolution.
+///
+/// Measurements are mapped onto the maximum resolution grid by replicating every zone onto the zones whose centers
+/// it contains, so the per-zone parameters (e.g. the zone mask and the calibration) apply to the maximum resolution grid.
+/// Unlike an interpolation, the edge of a hand is not blended with the background into zones at phantom distances.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct RuntimeResGestureRecognizer<
+    const MAX_RES_X: usize,
+    const MAX_RES_Y: usize,
+    const HISTORY_SIZE: usize,
+> {
+    recognizer: GestureRecognizer<MAX_RES_X, MAX_RES_Y, HISTORY_SIZE>,
+    res_x: usize,
+    res_y: usize,
+}
+
+impl<const MAX_RES_X: usize, const MAX_RES_Y: usize, const HISTORY_SIZE: usize>
+    RuntimeResGestureRecognizer<MAX_RES_X, MAX_RES_Y, HISTORY_SIZE>
+{
+    /// A new gesture recognizer with the given parameters, initially at the maximum resolution.
+    ///
+    /// Returns [RecognizerError::InitFailure] if the mounting orientation is not supported, see [GestureRecognizer::new].
+    pub fn new(
+        params: RecognizerParams,
+        sensor_params: SensorParams,
+    ) -> Result<Self, RecognizerError> {
+        Ok(Self {
+            recognizer: GestureRecognizer::new(params, sensor_params)?,
+            res_x: MAX_RES_X,
+            res_y: MAX_RES_Y,
+        })
+    }
+
+    /// Resets the gesture recognizer with the given parameters, see [GestureRecognizer::reset].
+    pub fn reset(
+        &mut self,
+        params: RecognizerParams,
+        sensor_params: SensorParams,
+        now: u32,
+    ) -> RecognizerStatus {
+        self.recognizer.reset(params, sensor_params, now)
+    }
+
+    /// Changes the resolution of the measurements.
+    ///
+    /// Resets the recognizer and clears the background, because they were built from measurements at the previous resolution.
+    /// The zone calibration is kept.
+    ///
+    /// Returns [RecognizerStatus::RecognizerStatusInvalidInput] if the resolution is zero or exceeds the maximum resolution.
+    pub fn set_resolution(&mut self, res_x: usize, res_y: usize, now: u32) -> RecognizerStatus {
+        if res_x == 0 || res_y == 0 || res_x > MAX_RES_X || res_y > MAX_RES_Y {
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        }
+
+        self.res_x = res_x;
+        self.res_y = res_y;
+        self.recognizer.clear_background();
+        self.recognizer.reset(
+            self.recognizer.params(),
+            self.recognizer.sensor_params(),
+            now,
+        )
+    }
+
+    /// The current resolution as (res_x, res_y).
+    pub fn resolution(&self) -> (usize, usize) {
+        (self.res_x, self.res_y)
+    }
+
+    /// Maps a measurement at the current resolution onto the maximum resolution grid.
+    ///
+    /// Every zone of the maximum resolution grid gets the distance of the zone that contains its center.
+    ///
+    /// Arguments:
+    /// - zone_dist: the measured distances of each zone in row-major order, invalid distances are represented by value -1.0.
+    /// - time_ms: the time of the measurement in milliseconds.
+    ///
+    /// Returns `None` if the length does not match the current resolution.
+    pub fn measurement(
+        &self,
+        zone_dist: &[f32],
+        time_ms: u32,
+    ) -> Option<SensorMeasurement<MAX_RES_X, MAX_RES_Y>> {
+        let mut measurement = SensorMeasurement::new(self.replicated(zone_dist, -1.0)?);
+        measurement.time_ms = time_ms;
+
+        Some(measurement)
+    }
+
+    /// Maps the zone quality at the current resolution onto the maximum resolution grid.
+    ///
+    /// Every zone of the maximum resolution grid gets the quality of the zone that contains its center.
+    ///
+    /// Returns `None` if the length does not match the current resolution.
+    pub fn zone_quality(
+        &self,
+        zone_quality: &[ZoneQuality],
+    ) -> Option<[[ZoneQuality; MAX_RES_X]; MAX_RES_Y]> {
+        self.replicated(zone_quality, ZoneQuality::unknown())
+    }
+
+    /// Replicates the values of the zones at the current resolution in row-major order onto the maximum resolution grid.
+    ///
+    /// Returns `None` if the length does not match the current resolution.
+    fn replicated<T: Copy>(&self, values: &[T], init: T) -> Option<[[T; MAX_RES_X]; MAX_RES_Y]> {
+        if values.len() != self.res_x * self.res_y {
+            return None;
+        }
+        let mut replicated = [[init; MAX_RES_X]; MAX_RES_Y];
+
+        for (pos_y, row) in replicated.iter_mut().enumerate() {
+            for (pos_x, value) in row.iter_mut().enumerate() {
+                *value = values[self.covering_zone(pos_x, pos_y)];
+            }
+        }
+
+        Some(replicated)
+    }
+
+    /// The row-major index of the zone at the current resolution that contains the center of the zone
+    /// of the maximum resolution grid.
+    fn covering_zone(&self, pos_x: usize, pos_y: usize) -> usize {
+        ((2 * pos_y + 1) * self.res_y / (2 * MAX_RES_Y)) * self.res_x
+            + (2 * pos_x + 1) * self.res_x / (2 * MAX_RES_X)
+    }
+
+    /// Updates the gesture recognizer with a new measurement at the current resolution, see [Self::measurement].
+    ///
+    /// `zone_quality` is the quality of each zone in row-major order, or empty if not available.
+    /// The time in the measurement must be monotonically increasing (usually coming from a systick timer).
+    pub fn update(
+        &mut self,
+        zone_dist: &[f32],
+        zone_quality: &[ZoneQuality],
+        time_ms: u32,
+        result: &mut RecognizerResult,
+    ) -> RecognizerStatus {
+        let Some(measurement) = self.measurement(zone_dist, time_ms) else {
+            *result = RecognizerResult::default();
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+        if zone_quality.is_empty() {
+            return self.recognizer.update(measurement, result);
+        }
+        let Some(zone_quality) = self.zone_quality(zone_quality) else {
+            *result = RecognizerResult::default();
+            return RecognizerStatus::RecognizerStatusInvalidInput;
+        };
+
+        self.recognizer
+            .update_with_quality(measurement, &zone_quality, result)
+    }
+
+    /// Gets the underlying recognizer at the maximum resolution.
+    pub fn recognizer(&self) -> &GestureRecognizer<MAX_RES_X, MAX_RES_Y, HISTORY_SIZE> {
+        &self.recognizer
+    }
+
+    /// Gets the underlying recognizer at the maximum resolution mutably, e.g. to calibrate it.
+    pub fn recognizer_mut(&mut self) -> &mut GestureRecognizer<MAX_RES_X, MAX_RES_Y, HISTORY_SIZE> {
+        &mut self.recognizer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuntimeResGestureRecognizer;
+    use crate::math::CoordsCartesian;
+    use crate::measurements::SensorParams;
+    use crate::{HandState, RecognizerParams, RecognizerResult, RecognizerStatus, ZoneQuality};
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn runtime_res_upsamples_zones() {
+        let mut recognizer = RuntimeResGestureRecognizer::<4, 4, 10>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        assert!(matches!(
+            recognizer.set_resolution(2, 2, 0),
+            RecognizerStatus::RecognizerStatusOk
+        ));
+        assert!(matches!(
+            recognizer.set_resolution(8, 8, 0),
+            RecognizerStatus::RecognizerStatusInvalidInput
+        ));
+
+        let measurement = recognizer.measurement(&[1.0, 2.0, 3.0, 4.0], 10).unwrap();
+        assert_eq!(
+            measurement.zone_dist,
+            [
+                [1.0, 1.0, 2.0, 2.0],
+                [1.0, 1.0, 2.0, 2.0],
+                [3.0, 3.0, 4.0, 4.0],
+                [3.0, 3.0, 4.0, 4.0],
+            ]
+        );
+        assert_eq!(measurement.time_ms, 10);
+        assert!(recognizer.measurement(&[1.0; 16], 10).is_none());
+    }
+
+    #[test]
+    fn runtime_res_hand_in_front_of_wall() {
+        let mut recognizer = RuntimeResGestureRecognizer::<8, 8, 10>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        recognizer.set_resolution(4, 4, 0);
+        // A hand in the two left zones of the two center rows, in front of a wall
+        let mut zone_dist = [1000.0; 16];
+        zone_dist[4] = 200.0;
+        zone_dist[8] = 200.0;
+
+        // No zone gets a distance between the hand and the wall
+        let measurement = recognizer.measurement(&zone_dist, 10).unwrap();
+        let hand_zones = measurement
+            .zone_dist
+            .iter()
+            .flatten()
+            .filter(|&&dist| dist == 200.0);
+        assert_eq!(hand_zones.count(), 8);
+        assert!(measurement
+            .zone_dist
+            .iter()
+            .flatten()
+            .all(|&dist| dist == 200.0 || dist == 1000.0));
+
+        // The hand is found at the center of its zones
+        let mut result = RecognizerResult::default();
+        recognizer.update(&zone_dist, &[], 10, &mut result);
+        let HandState::HandFound { hand_pos, .. } = result.hand_state else {
+            panic!("hand should be found");
+        };
+        let hand_pos = CoordsCartesian::from(hand_pos);
+        assert!(hand_pos.y < 0.0);
+        assert_relative_eq!(hand_pos.z, 0.0, epsilon = 5.0);
+    }
+
+    #[test]
+    fn runtime_res_symmetric() {
+        let mut recognizer = RuntimeResGestureRecognizer::<8, 8, 10>::new(
+            RecognizerParams::default(),
+            SensorParams::default_vl53l5cx(),
+        )
+        .unwrap();
+        recognizer.set_resolution(3, 3, 0);
+        // A hand in the center zone, in front of a wall
+        let mut zone_dist = [1000.0; 9];
+        zone_dist[4] = 200.0;
+        let mut zone_quality = [ZoneQuality::unknown(); 9];
+        zone_quality[4].target_status = 5;
+
+        let measurement = recognizer.measurement(&zone_dist, 10).unwrap();
+        let mapped_quality = recognizer.zone_quality(&zone_quality).unwrap();
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(
+                    measurement.zone_dist[y][x],
+                    measurement.zone_dist[7 - y][7 - x]
+                );
+                assert_eq!(
+                    mapped_quality[y][x].target_status,
+                    mapped_quality[7 - y][7 - x].target_status
+                );
+            }
+        }
+
+        // The hand is found in the center of the FOV, within a quarter of a zone
+        let mut result = RecognizerResult::default();
+        zone_dist = [-1.0; 9];
+        zone_dist[4] = 200.0;
+        recognizer.update(&zone_dist, &[], 10, &mut result);
+        let HandState::HandFound { hand_pos, .. } = result.hand_state else {
+            panic!("hand should be found");
+        };
+        let hand_pos = CoordsCartesian::from(hand_pos);
+        assert_relative_eq!(hand_pos.y, 0.0, epsilon = 5.0);
+        assert_relative_eq!(hand_pos.z, 0.0, epsilon = 5.0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Serde Support for Matrices.
+//!
+//! Serde only implements its traits for arrays up to a fixed length, not for const-generic arrays.
+//! Use with `#[serde(with = "crate::serde_matrix")]` on `[[T; COLS]; ROWS]` fields.
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes the matrix as a tuple of rows, which are tuples of their values.
+///
+/// Like serde serializes arrays, so formats that are not self-describing (e.g. postcard) store no lengths.
+pub(crate) fn serialize<S, T, const COLS: usize, const ROWS: usize>(
+    matrix: &[[T; COLS]; ROWS],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut rows = serializer.serialize_tuple(ROWS)?;
+    for row in matrix {
+        rows.serialize_element(&Row(row))?;
+    }
+
+    rows.end()
+}
+
+/// A row of the matrix, that is serialized as a tuple of its values.
+struct Row<'a, T, const N: usize>(&'a [T; N]);
+
+impl<T: Serialize, const N: usize> Serialize for Row<'_, T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = serializer.serialize_tuple(N)?;
+        for value in self.0 {
+            values.serialize_element(value)?;
+        }
+
+        values.end()
+    }
+}
+
+/// Deserializes the matrix from a tuple of rows, the number of rows and columns must match.
+pub(crate) fn deserialize<'de, D, T, const COLS: usize, const ROWS: usize>(
+    deserializer: D,
+) -> Result<[[T; COLS]; ROWS], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default + Copy,
+{
+    let Array(rows) = Array::<Array<T, COLS>, ROWS>::deserialize(deserializer)?;
+
+    Ok(rows.map(|row| row.0))
+}
+
+/// An array that is deserialized from a tuple of exactly its length.
+#[derive(Clone, Copy)]
+struct Array<T, const N: usize>([T; N]);
+
+impl<T: Default + Copy, const N: usize> Default for Array<T, N> {
+    fn default() -> Self {
+        Self([T::default(); N])
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for Array<T, N>
+where
+    T: Deserialize<'de> + Default + Copy,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+    }
+}
+
+struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+where
+    T: Deserialize<'de> + Default + Copy,
+{
+    type Value = Array<T, N>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of length {N}")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut array = Array::default();
+
+        for (i, value) in array.0.iter_mut().enumerate() {
+            *value = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(Error::invalid_length(N + 1, &self));
+        }
+
+        Ok(array)
+    }
+}
@@ -0,0 +1,265 @@
+//! Hand Tracking.
+//!
+//! All distance values are considered to be in millimeter.
+
+use crate::math::{CoordsCartesian, CoordsSpherical};
+use crate::measurements::HandFeatures;
+use crate::HandState;
+
+/// The constant-velocity kalman filter state of a single axis.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KalmanAxis {
+    /// The estimated position (mm).
+    pos: f32,
+    /// The estimated velocity (mm/s).
+    vel: f32,
+    /// The covariance of the state (pos, vel).
+    cov: [[f32; 2]; 2],
+}
+
+impl KalmanAxis {
+    fn zero() -> Self {
+        Self {
+            pos: 0.0,
+            vel: 0.0,
+            cov: [[0.0; 2]; 2],
+        }
+    }
+
+    /// Initializes the state with a measured position and an unknown velocity.
+    fn init(&mut self, pos: f32, measurement_var: f32) {
+        /// The initial variance of the velocity. Large, because the velocity is unknown.
+        const INITIAL_VEL_VAR: f32 = 1.0e6;
+
+        self.pos = pos;
+        self.vel = 0.0;
+        self.cov = [[measurement_var, 0.0], [0.0, INITIAL_VEL_VAR]];
+    }
+
+    /// Predicts the state `dt` seconds ahead, with `accel_var` being the variance of the acceleration.
+    fn predict(&mut self, dt: f32, accel_var: f32) {
+        let [[p00, p01], [p10, p11]] = self.cov;
+        let dt2 = dt * dt;
+
+        self.pos += self.vel * dt;
+        self.cov = [
+            [
+                p00 + dt * (p01 + p10) + dt2 * p11 + accel_var * dt2 * dt2 / 4.0,
+                p01 + dt * p11 + accel_var * dt2 * dt / 2.0,
+            ],
+            [
+                p10 + dt * p11 + accel_var * dt2 * dt / 2.0,
+                p11 + accel_var * dt2,
+            ],
+        ];
+    }
+
+    /// Corrects the state with a measured position, `measurement_var` being the variance of the measurement.
+    fn correct(&mut self, pos: f32, measurement_var: f32) {
+        let [[p00, p01], [p10, p11]] = self.cov;
+
+        let s = p00 + measurement_var;
+        if s <= 0.0 {
+            return;
+        }
+        let k0 = p00 / s;
+        let k1 = p10 / s;
+        let innovation = pos - self.pos;
+
+        self.pos += k0 * innovation;
+        self.vel += k1 * innovation;
+        self.cov = [
+            [(1.0 - k0) * p00, (1.0 - k0) * p01],
+            [p10 - k1 * p00, p11 - k1 * p01],
+        ];
+    }
+}
+
+/// Tracks the hand position with a constant-velocity kalman filter in cartesian space.
+///
+/// Predicts the position through short dropouts, where the hand is temporarily not found (e.g. because of noise).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct HandTracker {
+    accel_noise: f32,
+    measurement_noise: f32,
+    max_dropout_ms: u32,
+    /// The filter states of the x-, y- and z-axis.
+    axes: [KalmanAxis; 3],
+    last_update_ms: u32,
+    last_found_ms: u32,
+    tracking: bool,
+    /// The features of the last found hand.
+    hand_features: HandFeatures,
+}
+
+impl HandTracker {
+    /// A new hand tracker.
+    ///
+    /// Arguments:
+    /// - accel_noise: the standard deviation of the hand acceleration (mm/s^2)
+    /// - measurement_noise: the standard deviation of the measured hand position (mm)
+    /// - max_dropout_ms: how long the position is predicted when the hand is not found
+    pub fn new(accel_noise: f32, measurement_noise: f32, max_dropout_ms: u32) -> Self {
+        Self {
+            accel_noise,
+            measurement_noise,
+            max_dropout_ms,
+            axes: [KalmanAxis::zero(); 3],
+            last_update_ms: 0,
+            last_found_ms: 0,
+            tracking: false,
+            hand_features: HandFeatures::empty(),
+        }
+    }
+
+    /// Resets the tracker, the hand is then considered not tracked.
+    pub fn reset(&mut self) {
+        *self = Self::new(
+            self.accel_noise,
+            self.measurement_noise,
+            self.max_dropout_ms,
+        );
+    }
+
+    /// Updates the tracker with the recognized hand state at the given time.
+    ///
+    /// The time must be monotonically increasing.
+    ///
+    /// Returns the tracked hand state. It contains the filtered position when the hand was found,
+    /// the predicted position during a short dropout and [HandState::HandNotFound] else.
+    /// During a dropout the features of the last found hand are kept.
+    pub fn update(&mut self, hand_state: HandState, time_ms: u32) -> HandState {
+        let accel_var = self.accel_noise * self.accel_noise;
+        let measurement_var = self.measurement_noise * self.measurement_noise;
+
+        if self.tracking {
+            let dt = time_ms.saturating_sub(self.last_update_ms) as f32 / 1000.0;
+
+            for axis in self.axes.iter_mut() {
+                axis.predict(dt, accel_var);
+            }
+        }
+        self.last_update_ms = time_ms;
+
+        match hand_state {
+            HandState::HandFound {
+                hand_pos,
+                hand_features,
+            } => {
+                let measured = CoordsCartesian::from(hand_pos);
+                let measured = [measured.x, measured.y, measured.z];
+
+                for (axis, pos) in self.axes.iter_mut().zip(measured) {
+                    if self.tracking {
+                        axis.correct(pos, measurement_var);
+                    } else {
+                        axis.init(pos, measurement_var);
+                    }
+                }
+                self.tracking = true;
+                self.last_found_ms = time_ms;
+                self.hand_features = hand_features;
+            }
+            HandState::HandNotFound => {
+                if time_ms.saturating_sub(self.last_found_ms) > self.max_dropout_ms {
+                    self.tracking = false;
+                }
+            }
+        }
+
+        if self.tracking {
+            HandState::HandFound {
+                hand_pos: CoordsSpherical::from(self.position()),
+                hand_features: self.hand_features,
+            }
+        } else {
+            HandState::HandNotFound
+        }
+    }
+
+    /// Whether the hand is currently tracked.
+    pub fn is_tracking(&self) -> bool {
+        self.tracking
+    }
+
+    /// The estimated hand position (mm).
+    pub fn position(&self) -> CoordsCartesian {
+        CoordsCartesian {
+            x: self.axes[0].pos,
+            y: self.axes[1].pos,
+            z: self.axes[2].pos,
+        }
+    }
+
+    /// The estimated hand velocity (mm/s).
+    pub fn velocity(&self) -> CoordsCartesian {
+        CoordsCartesian {
+            x: self.axes[0].vel,
+            y: self.axes[1].vel,
+            z: self.axes[2].vel,
+        }
+    }
+
+    /// The covariance of the (position, velocity) state of the given axis (0: x, 1: y, 2: z).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn covariance(&self, axis: usize) -> [[f32; 2]; 2] {
+        self.axes[axis].cov
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HandTracker;
+    use crate::math::{CoordsCartesian, CoordsSpherical};
+    use crate::measurements::HandFeatures;
+    use crate::HandState;
+    use approx::assert_relative_eq;
+
+    fn found(x: f32, y: f32, z: f32) -> HandState {
+        HandState::HandFound {
+            hand_pos: CoordsSpherical::from(CoordsCartesian { x, y, z }),
+            hand_features: HandFeatures::empty(),
+        }
+    }
+
+    #[test]
+    fn tracker_estimates_velocity() {
+        let mut tracker = HandTracker::new(10000.0, 1.0, 100);
+
+        // Moving with 500 mm/s along the y-axis
+        for i in 0..20 {
+            tracker.update(found(200.0, -100.0 + 500.0 * i as f32 * 0.05, 0.0), i * 50);
+        }
+
+        assert_relative_eq!(tracker.velocity().x, 0.0, epsilon = 1.0);
+        assert_relative_eq!(tracker.velocity().y, 500.0, epsilon = 10.0);
+        assert_relative_eq!(tracker.position().y, 375.0, epsilon = 5.0);
+    }
+
+    #[test]
+    fn tracker_predicts_through_dropout() {
+        let mut tracker = HandTracker::new(10000.0, 1.0, 100);
+
+        for i in 0..20 {
+            tracker.update(found(200.0, -100.0 + 500.0 * i as f32 * 0.05, 0.0), i * 50);
+        }
+
+        let HandState::HandFound { hand_pos, .. } = tracker.update(HandState::HandNotFound, 1000)
+        else {
+            panic!("hand should still be tracked");
+        };
+        assert_relative_eq!(CoordsCartesian::from(hand_pos).y, 400.0, epsilon = 5.0);
+
+        tracker.update(HandState::HandNotFound, 1050);
+        assert!(matches!(
+            tracker.update(HandState::HandNotFound, 1100),
+            HandState::HandNotFound
+        ));
+        assert!(!tracker.is_tracking());
+    }
+}
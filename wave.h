@@ -29,10 +29,49 @@
 /**
  * The history size.
  *
- * Must be large enough to hold at least ca 2 seconds of data to be able to reliably recognize gestures.
+ * Must be large enough to hold at least ca 2 seconds of data to be able to reliably recognize gestures,
+ * and at least the `min_history_size` of the used sensor preset, e.g. 91 for the 4x4 presets at 60 Hz.
  */
 #define HISTORY_SIZE 60
 
+/**
+ * The number of targets per zone of multi-target measurements.
+ *
+ * For users of the C bindings:
+ * Change the value of this definition to match the configured number of targets per zone of the sensor.
+ *
+ * For VL53L5CX: Up to 4 (`VL53L5CX_NB_TARGET_PER_ZONE`).
+ */
+#define TARGETS_PER_ZONE 2
+
+/**
+ * The number of sensors of the fusion gesture recognizer.
+ *
+ * For users of the C bindings:
+ * Change the value of this definition to match the number of fused sensors.
+ */
+#define FUSION_SENSORS 2
+
+/**
+ * The number of fractional bits of [Fixed].
+ */
+#define FIXED_FRAC_BITS 12
+
+/**
+ * The maximum resolution in both directions for which zones can be masked.
+ */
+#define ZONE_MASK_MAX_RES 8
+
+/**
+ * The maximum resolution of the zone angle lookup table of the optical model.
+ */
+#define OPTICAL_LUT_MAX_RES 8
+
+/**
+ * The maximum window size of the median hand position filter.
+ */
+#define HAND_POS_FILTER_MEDIAN_MAX_WINDOW 9
+
 /**
  * A hand gesture.
  */
@@ -61,8 +100,131 @@ typedef enum Gesture {
    * A down swipe.
    */
   GestureSwipeDown,
+  /**
+   * A grab, the hand closes (e.g. from open palm to fist).
+   */
+  GestureGrab,
+  /**
+   * A release, the hand opens (e.g. from fist to open palm).
+   */
+  GestureRelease,
+  /**
+   * A clockwise rotation of the hand when looking at the sensor, like turning a key.
+   */
+  GestureRotateClockwise,
+  /**
+   * A counterclockwise rotation of the hand when looking at the sensor, like turning a key.
+   */
+  GestureRotateCounterClockwise,
 } Gesture;
 
+/**
+ * The filter that is applied to smooth the recognized hand position.
+ */
+typedef enum HandPosFilter {
+  /**
+   * The hand position is not smoothed.
+   */
+  HandPosFilterNone = 0,
+  /**
+   * Exponential moving average.
+   */
+  HandPosFilterEma,
+  /**
+   * One-euro filter, adapting the smoothing to the speed of the hand.
+   */
+  HandPosFilterOneEuro,
+  /**
+   * Median over a window of the last positions.
+   */
+  HandPosFilterMedian,
+} HandPosFilter;
+
+/**
+ * The classified pose of the hand.
+ */
+typedef enum HandPose {
+  /**
+   * The pose could not be classified.
+   */
+  HandPoseUnknown = 0,
+  /**
+   * An open palm facing the sensor.
+   */
+  HandPoseOpenPalm,
+  /**
+   * A fist.
+   */
+  HandPoseFist,
+  /**
+   * A single finger pointing at the sensor.
+   */
+  HandPoseFinger,
+} HandPose;
+
+/**
+ * The clockwise rotation that is applied to the zones when looking at the sensor, compensating the rotation of the
+ * sensor in its mounting.
+ */
+typedef enum MountingRotation {
+  /**
+   * The zones are not rotated.
+   */
+  MountingRotation0 = 0,
+  /**
+   * The zones are rotated by 90 degrees. Only supported for square resolutions.
+   */
+  MountingRotation90,
+  /**
+   * The zones are rotated by 180 degrees.
+   */
+  MountingRotation180,
+  /**
+   * The zones are rotated by 270 degrees. Only supported for square resolutions.
+   */
+  MountingRotation270,
+} MountingRotation;
+
+/**
+ * The optical model, mapping a zone to the direction of its center.
+ */
+typedef enum OpticalModel {
+  /**
+   * Every zone covers an equal share of the FOV angle.
+   */
+  OpticalModelLinear = 0,
+  /**
+   * A pinhole lens with radial distortion, the zones are equally sized on the image plane.
+   *
+   * The normalized image coordinates are scaled by `1 + optical_distortion * r^2`.
+   */
+  OpticalModelPinhole,
+  /**
+   * The zone angles are looked up from a table, e.g. from the zone angle data of the sensor vendor.
+   *
+   * Zones outside of [OPTICAL_LUT_MAX_RES] fall back to [OpticalModel::OpticalModelLinear].
+   */
+  OpticalModelLookupTable,
+} OpticalModel;
+
+/**
+ * A presence event.
+ */
+typedef enum PresenceEvent {
+  /**
+   * The presence did not change.
+   */
+  PresenceNone = 0,
+  /**
+   * Something entered the range of the sensor.
+   */
+  PresenceEntered,
+  /**
+   * Nothing is in the range of the sensor anymore.
+   */
+  PresenceLeft,
+} PresenceEvent;
+
 /**
  * The status of the gesture recognizer.
  */
@@ -82,146 +244,162 @@ typedef enum RecognizerStatus {
 } RecognizerStatus;
 
 /**
- * Represents a sensor measurement coming from the TOF sensor.
- *
- * Expects that the zones are already rotated and mirrored,
- * so that the zone with index \[0\]\[0\] is the top left corner when looking at the sensor.
+ * The available sensor presets.
  */
-typedef struct SensorMeasurement_RES_X__RES_Y {
+typedef enum SensorPresetId {
   /**
-   * The measured distances of each zone.
-   *
-   * Invalid distance measurements are represented by value -1.0.
+   * ST VL53L5CX in 4x4 mode.
    */
-  float zone_dist[RES_Y][RES_X];
+  SensorPresetVl53l5cx4x4 = 0,
   /**
-   * The time of the measurement in milliseconds. Must be monotonically increasing.
+   * ST VL53L5CX in 8x8 mode.
    */
-  uint32_t time_ms;
-} SensorMeasurement_RES_X__RES_Y;
-
-/**
- * Configurable sensor parameters. Different for every sensor.
- */
-typedef struct SensorParams {
+  SensorPresetVl53l5cx8x8,
   /**
-   * The horizontal FOV of the sensor.
+   * ST VL53L7CX in 4x4 mode.
    */
-  float fov_horizontal;
+  SensorPresetVl53l7cx4x4,
   /**
-   * The vertical FOV of the sensor.
+   * ST VL53L7CX in 8x8 mode.
    */
-  float fov_vertical;
-} SensorParams;
-
-/**
- * Parameters for gesture recognition.
- */
-typedef struct RecognizerParams {
+  SensorPresetVl53l7cx8x8,
   /**
-   * The furthest hand distance for gesture recognition.
+   * ST VL53L8CX in 4x4 mode.
    */
-  float gesture_threshold_dist;
+  SensorPresetVl53l8cx4x4,
   /**
-   * The time the hand has to be still to recognize a static hold.
+   * ST VL53L8CX in 8x8 mode.
    */
-  uint32_t static_hold_time_ms;
+  SensorPresetVl53l8cx8x8,
   /**
-   * How much the hand can move towards / away from the sensor while doing a static hold.
+   * ams TMF8820 in 3x3 mode.
    */
-  float static_hold_tolerance_dist;
+  SensorPresetTmf88203x3,
   /**
-   * How much the hand can move towards / away from the sensor while doing a swipe.
+   * ams TMF8821 in 4x4 mode.
    */
-  float swipe_tolerance_dist;
+  SensorPresetTmf88214x4,
   /**
-   * How much distance the hand has to travel to detect a horizontal swipe.
+   * ams TMF8828 in 8x8 mode.
    */
-  float swipe_horizontal_travel_dist;
+  SensorPresetTmf88288x8,
+} SensorPresetId;
+
+/**
+ * How the furthest hand distance for gesture recognition is determined.
+ */
+typedef enum ThresholdMode {
   /**
-   * How much distance the hand has to travel to detect a vertical swipe.
+   * The fixed configured threshold distance.
    */
-  float swipe_vertical_travel_dist;
-} RecognizerParams;
+  ThresholdModeFixed = 0,
+  /**
+   * The threshold distance adapts to the observed background depth and hand distances, within the configured bounds.
+   */
+  ThresholdModeAdaptive,
+} ThresholdMode;
 
 /**
- * Represents spherical coordinates in mathematical naming convention.
- * ([Reference](https://mathworld.wolfram.com/SphericalCoordinates.html))
+ * The temporal filter that is applied to every zone of the measurements.
  */
-typedef struct CoordsSpherical {
+typedef enum ZoneFilterMode {
   /**
-   * Distance to the origin.
+   * The zones are not filtered.
    */
-  float r;
+  ZoneFilterNone = 0,
   /**
-   * Angle with respect to x-axis (azimuth) (rad).
+   * The median of the last three measurements of a zone.
+   *
+   * A zone is only valid when at least two of the last three measurements are valid.
    */
-  float theta;
+  ZoneFilterMedian3,
   /**
-   * Angle with respect to polar / z-axis (zenith) (rad).
+   * Hysteresis on the valid / invalid transitions of a zone.
+   *
+   * A zone only changes between valid and invalid after the configured number of consecutive frames.
+   * While an invalid measurement is suppressed, the last valid distance is held.
    */
-  float phi;
-} CoordsSpherical;
+  ZoneFilterHysteresis,
+} ZoneFilterMode;
 
 /**
- * The recognized hand state.
+ * Represents a sensor measurement coming from the TOF sensor.
+ *
+ * The zones are expected in the order they are read out from the sensor.
+ * They are rotated and mirrored according to the mounting orientation of the [SensorParams] by the recognizer,
+ * so that afterwards the zone with index \[0\]\[0\] is the bottom left corner when looking at the sensor,
+ * with the rows going upwards and the columns going to the right.
+ *
+ * Previous versions documented the zone \[0\]\[0\] as the top left corner. Integrations that reversed the row order
+ * for this have to remove that step, or set `mounting_mirror_vertical` of the [SensorParams].
  */
-typedef enum HandState_Tag {
+typedef struct SensorMeasurement_RES_X__RES_Y {
   /**
-   * No hand was found.
+   * The measured distances of each zone.
+   *
+   * Invalid distance measurements are represented by value -1.0.
    */
-  HandNotFound,
+  float zone_dist[RES_Y][RES_X];
   /**
-   * Hand was found with this position.
+   * The time of the measurement in milliseconds. Must be monotonically increasing.
    */
-  HandFound,
-} HandState_Tag;
+  uint32_t time_ms;
+} SensorMeasurement_RES_X__RES_Y;
 
-typedef struct HandFound_Body {
+/**
+ * Quality information of a zone measurement, as reported by e.g. the ST VL53L5CX.
+ */
+typedef struct ZoneQuality {
   /**
-   * The hand position in spherical coordinates.
+   * The target status reported by the sensor, [ZoneQuality::TARGET_STATUS_UNKNOWN] if not available.
    */
-  struct CoordsSpherical hand_pos;
-} HandFound_Body;
-
-typedef struct HandState {
-  HandState_Tag tag;
-  union {
-    HandFound_Body hand_found;
-  };
-} HandState;
+  uint8_t target_status;
+  /**
+   * The signal rate per SPAD (kcps/SPAD), negative if not available.
+   */
+  float signal_per_spad;
+  /**
+   * The estimated standard deviation of the distance (mm), negative if not available.
+   */
+  float range_sigma;
+} ZoneQuality;
+/**
+ * The target status value for an unknown target status.
+ */
+#define ZoneQuality_TARGET_STATUS_UNKNOWN 255
 
 /**
- * A gesture prediction result.
+ * A single target of a zone in a multi-target measurement.
  */
-typedef struct RecognizerResult {
+typedef struct ZoneTarget {
   /**
-   * The current hand state.
+   * The measured distance of the target.
+   *
+   * Invalid targets are represented by value -1.0.
    */
-  struct HandState hand_state;
+  float dist;
   /**
-   * The recognized gesture, GestureNone if no gesture was recognized.
+   * The optional quality of the target. Set to [ZoneQuality::unknown] if not available.
    */
-  enum Gesture gesture;
-} RecognizerResult;
-
-typedef struct HistoryEntry_RES_X__RES_Y {
-  struct SensorMeasurement_RES_X__RES_Y measurement;
-  struct HandState hand_state;
-} HistoryEntry_RES_X__RES_Y;
+  struct ZoneQuality quality;
+} ZoneTarget;
 
 /**
- * The gesture recognizer.
+ * Represents a sensor measurement with multiple targets per zone, e.g. a hand in front of a far wall.
  *
- * Is initially configured through parameters and gets fed measurements and time and predicts gestures.
+ * Sensors like the ST VL53L5CX and VL53L7CX can report up to four targets per zone.
+ * The zones are expected in the same orientation as in [SensorMeasurement].
  */
-typedef struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE {
-  struct RecognizerParams params;
-  struct SensorParams sensor_params;
-  uint32_t start_time;
-  struct HistoryEntry_RES_X__RES_Y history[HISTORY_SIZE];
-  uintptr_t received_measurements;
-} GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE;
+typedef struct MultiTargetMeasurement_RES_X__RES_Y__TARGETS_PER_ZONE {
+  /**
+   * The targets of each zone.
+   */
+  struct ZoneTarget zone_targets[RES_Y][RES_X][TARGETS_PER_ZONE];
+  /**
+   * The time of the measurement in milliseconds. Must be monotonically increasing.
+   */
+  uint32_t time_ms;
+} MultiTargetMeasurement_RES_X__RES_Y__TARGETS_PER_ZONE;
 
 /**
  * Cartesian coordinates.
@@ -242,21 +420,849 @@ typedef struct CoordsCartesian {
 } CoordsCartesian;
 
 /**
- * Creates an invalid measurement (distances are set to `-1.0`).
- */
-struct SensorMeasurement_RES_X__RES_Y sensor_measurement_invalid(void);
-
-/**
- * Default sensor parameters for the ST VL53L5CX TOF-Sensor.
+ * Configurable sensor parameters. Different for every sensor.
  */
-struct SensorParams sensor_params_default_vl53l5cx(void);
+typedef struct SensorParams {
+  /**
+   * The horizontal FOV of the sensor.
+   */
+  float fov_horizontal;
+  /**
+   * The vertical FOV of the sensor.
+   */
+  float fov_vertical;
+  /**
+   * The optical model, mapping a zone to the direction of its center.
+   */
+  enum OpticalModel optical_model;
+  /**
+   * The radial distortion coefficient of [OpticalModel::OpticalModelPinhole].
+   */
+  float optical_distortion;
+  /**
+   * The horizontal angle of every zone center for [OpticalModel::OpticalModelLookupTable] (deg), positive to the right.
+   *
+   * Indexed like the oriented zones.
+   */
+  float optical_lut_angle_hor[OPTICAL_LUT_MAX_RES][OPTICAL_LUT_MAX_RES];
+  /**
+   * The vertical angle of every zone center for [OpticalModel::OpticalModelLookupTable] (deg), positive upwards.
+   *
+   * Indexed like the oriented zones, so the first row is the bottom row and has the lowest angles.
+   */
+  float optical_lut_angle_vert[OPTICAL_LUT_MAX_RES][OPTICAL_LUT_MAX_RES];
+  /**
+   * The rotation of the zones, compensating the rotation of the sensor in its mounting.
+   */
+  enum MountingRotation mounting_rotation;
+  /**
+   * Whether the zones are mirrored horizontally, applied after the rotation.
+   */
+  bool mounting_mirror_horizontal;
+  /**
+   * Whether the zones are mirrored vertically, applied after the rotation.
+   */
+  bool mounting_mirror_vertical;
+  /**
+   * The rotation from the sensor frame into the user-facing frame, in which the gestures are classified.
+   *
+   * The identity when the sensor directly faces the user, see [SensorParams::set_pose_euler].
+   */
+  float pose_rotation[3][3];
+  /**
+   * The position of the sensor in the user-facing frame (mm), e.g. to place multiple sensors in a shared frame.
+   */
+  struct CoordsCartesian pose_translation;
+  /**
+   * The mask of zones that are ignored, e.g. zones shadowed by the enclosure or pointed at a fixed obstacle.
+   *
+   * Bit `pos_y * 8 + pos_x` is set for the masked zone at the index \[pos_y\]\[pos_x\] of the oriented zones,
+   * so only zones up to a resolution of [ZONE_MASK_MAX_RES] can be masked.
+   */
+  uint64_t zone_mask;
+  /**
+   * The accepted target status values of the zone quality. Bit `n` is set when target status `n` is accepted.
+   */
+  uint32_t quality_valid_target_status;
+  /**
+   * The minimum accepted signal rate per SPAD of the zone quality (kcps/SPAD).
+   */
+  float quality_min_signal_per_spad;
+  /**
+   * The maximum accepted range sigma of the zone quality (mm).
+   */
+  float quality_max_range_sigma;
+  /**
+   * Targets of multi-target measurements that are closer are skipped, e.g. reflections of a cover glass.
+   */
+  float min_target_dist;
+} SensorParams;
 
 /**
- * Default recognizer parameters, providing a good starting point for gesture recognition.
+ * Parameters for gesture recognition.
  */
-struct RecognizerParams recognizer_params_default(void);
-
-/**
+typedef struct RecognizerParams {
+  /**
+   * The furthest hand distance for gesture recognition.
+   *
+   * Only used with [ThresholdMode::ThresholdModeFixed].
+   */
+  float gesture_threshold_dist;
+  /**
+   * The time the hand has to be still to recognize a static hold.
+   */
+  uint32_t static_hold_time_ms;
+  /**
+   * How much the hand can move towards / away from the sensor while doing a static hold.
+   */
+  float static_hold_tolerance_dist;
+  /**
+   * How much the hand can move towards / away from the sensor while doing a swipe.
+   */
+  float swipe_tolerance_dist;
+  /**
+   * How much distance the hand has to travel to detect a horizontal swipe.
+   */
+  float swipe_horizontal_travel_dist;
+  /**
+   * How much distance the hand has to travel to detect a vertical swipe.
+   */
+  float swipe_vertical_travel_dist;
+  /**
+   * Enables the hand tracker, that filters the hand position and predicts it through short dropouts.
+   *
+   * When disabled, the tracker is not updated.
+   */
+  bool tracker_enabled;
+  /**
+   * The process noise of the hand tracker, the standard deviation of the hand acceleration (mm/s^2).
+   */
+  float tracker_accel_noise;
+  /**
+   * The measurement noise of the hand tracker, the standard deviation of the measured hand position.
+   */
+  float tracker_measurement_noise;
+  /**
+   * How long the hand tracker predicts the hand position while the hand is temporarily not found.
+   */
+  uint32_t tracker_max_dropout_ms;
+  /**
+   * The filter that smoothes the hand position before it is put into the result and history.
+   */
+  enum HandPosFilter hand_pos_filter;
+  /**
+   * The smoothing factor of the exponential moving average filter, in range `(0.0, 1.0]`.
+   */
+  float hand_pos_filter_ema_alpha;
+  /**
+   * The minimum cutoff frequency of the one-euro filter (Hz).
+   */
+  float hand_pos_filter_one_euro_min_cutoff;
+  /**
+   * How much the cutoff frequency of the one-euro filter increases with the hand speed.
+   */
+  float hand_pos_filter_one_euro_beta;
+  /**
+   * The cutoff frequency of the hand speed of the one-euro filter (Hz).
+   */
+  float hand_pos_filter_one_euro_d_cutoff;
+  /**
+   * The window size of the median filter, up to [HAND_POS_FILTER_MEDIAN_MAX_WINDOW].
+   */
+  uint32_t hand_pos_filter_median_window;
+  /**
+   * The temporal filter that is applied to every zone of the measurements before the hand is recognized.
+   */
+  enum ZoneFilterMode zone_filter;
+  /**
+   * The number of consecutive frames until a zone changes between valid and invalid, for the hysteresis zone filter.
+   */
+  uint32_t zone_filter_hysteresis_frames;
+  /**
+   * How much closer than the calibrated background a zone has to be to count towards hand recognition.
+   */
+  float background_min_diff_dist;
+  /**
+   * How fast the calibrated background adapts to changes of the static scene, per frame in range `[0.0, 1.0]`.
+   */
+  float background_adapt_rate;
+  /**
+   * The number of consecutive frames a static object has to persist in a zone without calibrated background
+   * until it is learned into the background. Zero disables this.
+   *
+   * It should be much longer than any gesture, so that a hand held still is not learned.
+   */
+  uint32_t background_persist_frames;
+  /**
+   * How the furthest hand distance for gesture recognition is determined.
+   */
+  enum ThresholdMode gesture_threshold_mode;
+  /**
+   * The lower bound of the adaptive threshold distance.
+   */
+  float adaptive_threshold_min_dist;
+  /**
+   * The upper bound of the adaptive threshold distance.
+   */
+  float adaptive_threshold_max_dist;
+  /**
+   * How far in front of the observed background depth the adaptive threshold distance stays.
+   */
+  float adaptive_threshold_background_margin;
+  /**
+   * How far behind the average observed hand distance the adaptive threshold distance is.
+   */
+  float adaptive_threshold_hand_margin;
+  /**
+   * How fast the adaptive threshold distance follows the observed distances, per frame in range `[0.0, 1.0]`.
+   */
+  float adaptive_threshold_rate;
+  /**
+   * How far behind the nearest zone the zones connected to it still belong to the hand.
+   */
+  float hand_depth_range;
+  /**
+   * The largest area of a hand (mm^2). Larger objects, e.g. forearms or bodies walking past, are rejected.
+   *
+   * Zero disables the rejection.
+   */
+  float hand_max_area;
+  /**
+   * The largest area of a fist (mm^2). Larger hands are classified as open palm.
+   *
+   * Its square root is the typical width of a fist, compact hands up to 1.5 times as wide are classified as fist.
+   */
+  float hand_fist_max_area;
+  /**
+   * The largest area of a pointing finger (mm^2).
+   *
+   * Its square root is the largest width of a finger, wider hands are not classified as finger.
+   */
+  float hand_finger_max_area;
+  /**
+   * The time in which the hand area has to change to detect a grab or release.
+   *
+   * Each half of it must contain at least three measurements with a hand.
+   */
+  uint32_t grab_time_ms;
+  /**
+   * The ratio of the newer to the older hand area at or below which a grab is detected.
+   * A release is detected at or above its inverse.
+   */
+  float grab_area_ratio;
+  /**
+   * How much the hand can move while doing a grab or release.
+   */
+  float grab_tolerance_dist;
+  /**
+   * The time in which the hand has to rotate to detect a rotate gesture.
+   */
+  uint32_t rotate_time_ms;
+  /**
+   * How much the hand has to rotate to detect a rotate gesture (deg).
+   */
+  float rotate_angle;
+  /**
+   * How much the hand can move while doing a rotate gesture.
+   */
+  float rotate_tolerance_dist;
+  /**
+   * How elongated the hand has to be for its orientation to be considered, in range `[0.0, 1.0]`.
+   */
+  float rotate_min_elongation;
+  /**
+   * Enables the low-power presence mode.
+   *
+   * Only the nearest zone distance is checked against the presence threshold, after the zone mask and the
+   * calibrated background were applied. The zone filter and the full gesture recognition only run while
+   * something is present.
+   *
+   * Not supported by the fusion gesture recognizer, which fails to initialize and reset when it is enabled.
+   */
+  bool presence_mode;
+  /**
+   * The approach threshold distance of the nearest zone for presence detection.
+   */
+  float presence_threshold_dist;
+  /**
+   * The number of consecutive frames within the presence threshold until presence is entered.
+   */
+  uint32_t presence_enter_frames;
+  /**
+   * The number of consecutive frames beyond the presence threshold until presence is left.
+   */
+  uint32_t presence_leave_frames;
+  /**
+   * The maximum distance between the hands found by multiple sensors to be fused into one hand.
+   *
+   * Hands farther away from the nearest hand are ignored by the fusion gesture recognizer.
+   */
+  float fusion_max_hand_dist;
+} RecognizerParams;
+
+/**
+ * A sensor preset: the sensor parameters with the matching recommended recognizer parameters.
+ *
+ * The recognizer must be created with the resolution of the preset and a history size of at least `min_history_size`,
+ * e.g. the 4x4 presets at 60 Hz need a history size of 91 for the static hold time of 1.5 seconds.
+ */
+typedef struct SensorPreset {
+  /**
+   * The horizontal resolution of the sensor mode.
+   */
+  uint32_t res_x;
+  /**
+   * The vertical resolution of the sensor mode.
+   */
+  uint32_t res_y;
+  /**
+   * The typical frame rate of the sensor mode (Hz).
+   */
+  float frame_rate_hz;
+  /**
+   * The maximum ranging distance of the sensor from its datasheet (mm).
+   *
+   * Informational, the threshold distances of the recognizer parameters do not depend on it.
+   */
+  float max_dist;
+  /**
+   * The minimum history size for the recognizer parameters at the frame rate, see [min_history_size].
+   */
+  uint32_t min_history_size;
+  /**
+   * The sensor parameters.
+   */
+  struct SensorParams sensor_params;
+  /**
+   * The recommended recognizer parameters.
+   */
+  struct RecognizerParams recognizer_params;
+} SensorPreset;
+
+/**
+ * Represents spherical coordinates in mathematical naming convention.
+ * ([Reference](https://mathworld.wolfram.com/SphericalCoordinates.html))
+ */
+typedef struct CoordsSpherical {
+  /**
+   * Distance to the origin.
+   */
+  float r;
+  /**
+   * Angle with respect to x-axis (azimuth) (rad).
+   */
+  float theta;
+  /**
+   * Angle with respect to polar / z-axis (zenith) (rad).
+   */
+  float phi;
+} CoordsSpherical;
+
+/**
+ * Size and shape features of the recognized hand, computed from the zones that belong to it.
+ */
+typedef struct HandFeatures {
+  /**
+   * The estimated physical area of the hand (mm^2).
+   */
+  float area;
+  /**
+   * The horizontal extent of the hand.
+   */
+  float width;
+  /**
+   * The vertical extent of the hand.
+   */
+  float height;
+  /**
+   * How compact the hand is in range `(0.0, 1.0]`, the ratio of its zones to the zones of its bounding box.
+   */
+  float compactness;
+  /**
+   * The number of zones that belong to the hand.
+   */
+  uint32_t zones;
+  /**
+   * The classified pose of the hand.
+   */
+  enum HandPose pose;
+  /**
+   * The in-plane rotation angle of the principal axis of the hand (rad), in range `[-PI/2, PI/2]`.
+   *
+   * Measured from the horizontal axis, counterclockwise when looking at the sensor.
+   */
+  float orientation;
+  /**
+   * How elongated the hand is in range `[0.0, 1.0]`, `0.0` for round and `1.0` for line shaped hands.
+   *
+   * The orientation is only meaningful for elongated hands.
+   */
+  float elongation;
+} HandFeatures;
+
+/**
+ * The recognized hand state.
+ */
+typedef enum HandState_Tag {
+  /**
+   * No hand was found.
+   */
+  HandNotFound,
+  /**
+   * Hand was found with this position.
+   */
+  HandFound,
+} HandState_Tag;
+
+typedef struct HandFound_Body {
+  /**
+   * The hand position in spherical coordinates, in the user-facing frame of the sensor pose.
+   */
+  struct CoordsSpherical hand_pos;
+  /**
+   * The size and shape features of the hand.
+   */
+  struct HandFeatures hand_features;
+} HandFound_Body;
+
+typedef struct HandState {
+  HandState_Tag tag;
+  union {
+    HandFound_Body hand_found;
+  };
+} HandState;
+
+/**
+ * A gesture prediction result.
+ */
+typedef struct RecognizerResult {
+  /**
+   * The current hand state.
+   */
+  struct HandState hand_state;
+  /**
+   * The recognized gesture, GestureNone if no gesture was recognized.
+   */
+  enum Gesture gesture;
+  /**
+   * The presence event in presence mode, PresenceNone if the presence did not change.
+   */
+  enum PresenceEvent presence_event;
+} RecognizerResult;
+
+/**
+ * A signed fixed-point number with 12 fractional bits, stored in a 32-bit integer (Q19.12).
+ *
+ * Distances in millimeter are covered up to about ±524 m with a resolution of 1/4096 mm.
+ * Multiplications and divisions use 64-bit intermediates and round to the nearest value.
+ * All arithmetic operations saturate at the bounds, a division by zero saturates towards the sign of the dividend.
+ */
+typedef int32_t Fixed;
+/**
+ * Zero.
+ */
+#define Fixed_ZERO 0
+/**
+ * One.
+ */
+#define Fixed_ONE (1 << FIXED_FRAC_BITS)
+/**
+ * π/2.
+ */
+#define Fixed_FRAC_PI_2 6434
+/**
+ * π.
+ */
+#define Fixed_PI 12868
+/**
+ * 2π.
+ */
+#define Fixed_TAU 25736
+
+#if !defined(WAVE_FIXED_POINT)
+/**
+ * The real number type of the per-zone computations of the recognizer.
+ *
+ * It is [Fixed] when the `fixed-point` feature is enabled, else `f32`.
+ */
+typedef float Scalar;
+#endif
+
+#if defined(WAVE_FIXED_POINT)
+/**
+ * The real number type of the per-zone computations of the recognizer.
+ *
+ * It is [Fixed] when the `fixed-point` feature is enabled, else `f32`.
+ */
+typedef Fixed Scalar;
+#endif
+
+/**
+ * The directions and sizes of the zones, precomputed from the sensor parameters.
+ *
+ * Computed once with floats, so the per-zone computations need no trigonometric functions of the real number type.
+ */
+typedef struct ZoneGeometry_Scalar__RES_X__RES_Y {
+  /**
+   * The unit vector `[x, y, z]` pointing to the center of each zone.
+   */
+  Scalar direction[RES_Y][RES_X][3];
+  /**
+   * The angular size of each zone as `[horizontal, vertical]` angles (rad), see [SensorParams::zone_size_angles].
+   */
+  Scalar size[RES_Y][RES_X][2];
+} ZoneGeometry_Scalar__RES_X__RES_Y;
+
+/**
+ * Per-zone range calibration, correcting range offsets and the crosstalk of a cover glass.
+ *
+ * The crosstalk is modeled as a bias that is proportional to the measured distance,
+ * so the corrected distance of a zone is `dist + offset + crosstalk * dist`.
+ * The zones are indexed after the mounting orientation was applied.
+ */
+typedef struct ZoneCalibration_RES_X__RES_Y {
+  /**
+   * The range offset of each zone.
+   */
+  float offset[RES_Y][RES_X];
+  /**
+   * The crosstalk coefficient of each zone.
+   */
+  float crosstalk[RES_Y][RES_X];
+} ZoneCalibration_RES_X__RES_Y;
+
+/**
+ * Filters every zone of the measurements over time, suppressing zones that flicker between valid and invalid.
+ */
+typedef struct ZoneFilter_RES_X__RES_Y {
+  enum ZoneFilterMode mode;
+  uint32_t hysteresis_frames;
+  /**
+   * The last two unfiltered distances of every zone, the most recent first.
+   */
+  float prev[2][RES_Y][RES_X];
+  /**
+   * The current output of the hysteresis.
+   */
+  float held[RES_Y][RES_X];
+  /**
+   * The number of consecutive frames contradicting the current validity of the hysteresis.
+   */
+  uint32_t counter[RES_Y][RES_X];
+} ZoneFilter_RES_X__RES_Y;
+
+/**
+ * A model of the static scene in front of the sensor, e.g. a ceiling, a table surface or a nearby wall.
+ *
+ * Can be exported and stored, and later be restored.
+ */
+typedef struct BackgroundModel_RES_X__RES_Y {
+  /**
+   * The background distance of each zone.
+   *
+   * Zones without background in range are represented by value -1.0.
+   */
+  float zone_dist[RES_Y][RES_X];
+  /**
+   * The number of valid distances the background distance of each zone was learned from.
+   */
+  uint16_t zone_frames[RES_Y][RES_X];
+  /**
+   * The number of frames the model was learned from. The model is only used when it was learned from at least one frame.
+   */
+  uint32_t frames;
+  /**
+   * The distance of a static object in each zone without background, that is not learned yet.
+   *
+   * Zones without such an object are represented by value -1.0.
+   */
+  float candidate_dist[RES_Y][RES_X];
+  /**
+   * The number of consecutive frames the static object of each zone without background persisted.
+   */
+  uint16_t candidate_frames[RES_Y][RES_X];
+} BackgroundModel_RES_X__RES_Y;
+
+/**
+ * Adapts the threshold distance to the observed background depth and hand distances.
+ *
+ * The threshold stays in front of the background and follows the average hand distance with a margin,
+ * so that it narrows when the hands are close to the sensor and widens when they are further away.
+ */
+typedef struct AdaptiveThreshold {
+  /**
+   * The observed background depth (mm).
+   */
+  float background_dist;
+  /**
+   * The average observed hand distance (mm), negative when no hand was observed yet.
+   */
+  float hand_dist;
+} AdaptiveThreshold;
+
+/**
+ * The preprocessing and hand recognition of the measurements of a single sensor.
+ */
+typedef struct SensorPipeline_RES_X__RES_Y {
+  struct SensorParams sensor_params;
+  /**
+   * The zone geometry of the sensor parameters, for the per-zone computations.
+   */
+  struct ZoneGeometry_Scalar__RES_X__RES_Y geometry;
+  struct ZoneCalibration_RES_X__RES_Y calibration;
+  struct ZoneFilter_RES_X__RES_Y zone_filter;
+  struct BackgroundModel_RES_X__RES_Y background;
+  struct AdaptiveThreshold adaptive_threshold;
+} SensorPipeline_RES_X__RES_Y;
+
+typedef struct HistoryEntry_RES_X__RES_Y {
+  /**
+   * The time of the measurement in milliseconds.
+   */
+  uint32_t time_ms;
+  /**
+   * The index of the sensor the zone distances are from.
+   */
+  uintptr_t sensor;
+  /**
+   * The distances of each zone of the preprocessed measurement.
+   */
+  float zone_dist[RES_Y][RES_X];
+  struct HandState hand_state;
+} HistoryEntry_RES_X__RES_Y;
+
+/**
+ * The history of the recognized hand states, from which the gestures are recognized.
+ */
+typedef struct GestureHistory_RES_X__RES_Y__HISTORY_SIZE {
+  /**
+   * The entries, the most recent first.
+   */
+  struct HistoryEntry_RES_X__RES_Y entries[HISTORY_SIZE];
+  uintptr_t received_measurements;
+} GestureHistory_RES_X__RES_Y__HISTORY_SIZE;
+
+/**
+ * Cheaply detects the presence of something in the range of the sensor, debounced over several frames.
+ *
+ * Only the distance of the nearest zone is compared to the approach threshold.
+ */
+typedef struct PresenceDetector {
+  float threshold_dist;
+  uint32_t enter_frames;
+  uint32_t leave_frames;
+  bool present;
+  /**
+   * The number of consecutive frames contradicting the current presence.
+   */
+  uint32_t counter;
+} PresenceDetector;
+
+/**
+ * Streaming exponential moving average filter.
+ */
+typedef struct EmaFilter {
+  float alpha;
+  float value;
+  bool initialized;
+} EmaFilter;
+
+/**
+ * Streaming one-euro filter, a low-pass filter with a cutoff frequency adapting to the speed of the signal.
+ *
+ * Slow changes are smoothed heavily, fast changes pass with low latency.
+ * ([Reference](https://gery.casiez.net/1euro/))
+ */
+typedef struct OneEuroFilter {
+  float min_cutoff;
+  float beta;
+  float d_cutoff;
+  float value;
+  float deriv;
+  uint32_t time_ms;
+  bool initialized;
+} OneEuroFilter;
+
+/**
+ * Streaming median filter over a window of the last values.
+ *
+ * `N` is the capacity, the window size can be configured up to it.
+ */
+typedef struct MedianFilter_HAND_POS_FILTER_MEDIAN_MAX_WINDOW {
+  float window[HAND_POS_FILTER_MEDIAN_MAX_WINDOW];
+  uintptr_t window_size;
+  uintptr_t len;
+  uintptr_t next;
+} MedianFilter_HAND_POS_FILTER_MEDIAN_MAX_WINDOW;
+
+/**
+ * Smoothes the hand position with the configured filter, independently for every cartesian axis.
+ */
+typedef struct HandPosSmoother {
+  enum HandPosFilter kind;
+  struct EmaFilter ema[3];
+  struct OneEuroFilter one_euro[3];
+  struct MedianFilter_HAND_POS_FILTER_MEDIAN_MAX_WINDOW median[3];
+} HandPosSmoother;
+
+/**
+ * The constant-velocity kalman filter state of a single axis.
+ */
+typedef struct KalmanAxis {
+  /**
+   * The estimated position (mm).
+   */
+  float pos;
+  /**
+   * The estimated velocity (mm/s).
+   */
+  float vel;
+  /**
+   * The covariance of the state (pos, vel).
+   */
+  float cov[2][2];
+} KalmanAxis;
+
+/**
+ * Tracks the hand position with a constant-velocity kalman filter in cartesian space.
+ *
+ * Predicts the position through short dropouts, where the hand is temporarily not found (e.g. because of noise).
+ */
+typedef struct HandTracker {
+  float accel_noise;
+  float measurement_noise;
+  uint32_t max_dropout_ms;
+  /**
+   * The filter states of the x-, y- and z-axis.
+   */
+  struct KalmanAxis axes[3];
+  uint32_t last_update_ms;
+  uint32_t last_found_ms;
+  bool tracking;
+  /**
+   * The features of the last found hand.
+   */
+  struct HandFeatures hand_features;
+} HandTracker;
+
+/**
+ * The gesture recognizer.
+ *
+ * Is initially configured through parameters and gets fed measurements and time and predicts gestures.
+ */
+typedef struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE {
+  struct RecognizerParams params;
+  uint32_t start_time;
+  struct SensorPipeline_RES_X__RES_Y pipeline;
+  struct GestureHistory_RES_X__RES_Y__HISTORY_SIZE history;
+  struct PresenceDetector presence;
+  struct HandPosSmoother smoother;
+  struct HandTracker tracker;
+} GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE;
+
+/**
+ * A gesture recognizer with a resolution that is selected at runtime, up to the compile-time maximum resolution.
+ *
+ * Measurements are mapped onto the maximum resolution grid by replicating every zone onto the zones whose centers
+ * it contains, so the per-zone parameters (e.g. the zone mask and the calibration) apply to the maximum resolution grid.
+ * Unlike an interpolation, the edge of a hand is not blended with the background into zones at phantom distances.
+ */
+typedef struct RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE {
+  struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE recognizer;
+  uintptr_t res_x;
+  uintptr_t res_y;
+} RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE;
+
+/**
+ * Computes a [ZoneCalibration] from frames of a flat target, that faces the sensor at a known distance.
+ *
+ * With frames at a single target distance only the offsets can be computed.
+ * With frames at multiple target distances the crosstalk is computed as well, through a least squares fit of the error.
+ */
+typedef struct ZoneCalibrator_RES_X__RES_Y {
+  uint32_t n[RES_Y][RES_X];
+  float sum_dist[RES_Y][RES_X];
+  float sum_dist_sq[RES_Y][RES_X];
+  float sum_err[RES_Y][RES_X];
+  float sum_dist_err[RES_Y][RES_X];
+} ZoneCalibrator_RES_X__RES_Y;
+
+/**
+ * A gesture recognizer fusing the measurements of multiple sensors, e.g. mounted side by side to cover a wider area.
+ *
+ * Every sensor is placed in a shared user-facing frame through the pose of its sensor parameters.
+ * The hands found by the sensors are fused into one hand, so gestures crossing from the FOV
+ * of one sensor into the FOV of another are recognized as one continuous gesture.
+ * The hands found by the sensors within [RecognizerParams::fusion_max_hand_dist] of the nearest hand are fused,
+ * the others are ignored. The presence mode is not supported.
+ */
+typedef struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS {
+  struct RecognizerParams params;
+  uint32_t start_time;
+  struct SensorPipeline_RES_X__RES_Y pipelines[FUSION_SENSORS];
+  struct GestureHistory_RES_X__RES_Y__HISTORY_SIZE history;
+  struct HandPosSmoother smoother;
+  struct HandTracker tracker;
+} FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS;
+
+/**
+ * Creates an invalid measurement (distances are set to `-1.0`).
+ */
+struct SensorMeasurement_RES_X__RES_Y sensor_measurement_invalid(void);
+
+/**
+ * Creates an invalid multi-target measurement (all targets are set to invalid).
+ */
+struct MultiTargetMeasurement_RES_X__RES_Y__TARGETS_PER_ZONE multi_target_measurement_invalid(void);
+
+/**
+ * Default sensor parameters for the ST VL53L5CX TOF-Sensor.
+ */
+struct SensorParams sensor_params_default_vl53l5cx(void);
+
+/**
+ * Default sensor parameters for the ST VL53L7CX TOF-Sensor.
+ */
+struct SensorParams sensor_params_default_vl53l7cx(void);
+
+/**
+ * Default sensor parameters for the ST VL53L8CX TOF-Sensor.
+ */
+struct SensorParams sensor_params_default_vl53l8cx(void);
+
+/**
+ * Default sensor parameters for the ams TMF8820, TMF8821 and TMF8828 TOF-Sensors.
+ */
+struct SensorParams sensor_params_default_tmf882x(void);
+
+/**
+ * Gets the sensor preset with the given id, containing the sensor parameters and the recommended recognizer parameters.
+ *
+ * The resolution of the preset must match `RES_X` and `RES_Y`.
+ */
+struct SensorPreset sensor_preset_get(enum SensorPresetId id);
+
+/**
+ * Masks the zone at the given position in the sensor parameters, so that it is ignored.
+ *
+ * Positions outside of `ZONE_MASK_MAX_RES` are ignored.
+ */
+void sensor_params_mask_zone(struct SensorParams *sensor_params, uintptr_t pos_x, uintptr_t pos_y);
+
+/**
+ * Sets the pose of the sensor relative to the user from euler angles (deg).
+ */
+void sensor_params_set_pose_euler(struct SensorParams *sensor_params,
+                                  float yaw,
+                                  float pitch,
+                                  float roll);
+
+/**
+ * Default recognizer parameters, providing a good starting point for gesture recognition.
+ */
+struct RecognizerParams recognizer_params_default(void);
+
+/**
  * Default result, meaning no hand is found and no gestures are recognized.
  *
  * Used to initialize the result before passing it to the recognizer.
@@ -264,17 +1270,27 @@ struct RecognizerParams recognizer_params_default(void);
 struct RecognizerResult recognizer_result_default(void);
 
 /**
- * A new gesture recognizer with the given parameters.
+ * Initializes a new gesture recognizer with the given parameters.
  *
  * The sensor parameters have preconfigured defaults for common TOF-Sensors such as the ST VL53L5CX.
+ * Rotations by 90 or 270 degrees of the mounting orientation require `RES_X` and `RES_Y` to be equal.
+ * Returns `RecognizerStatusInitFailure` and leaves the gesture recognizer untouched if the mounting orientation
+ * is not supported, `RecognizerStatusInvalidInput` if `gesture_recognizer` is `NULL`.
+ *
+ * # Safety
+ *
+ * `gesture_recognizer` must be `NULL` or point to memory valid for writing a gesture recognizer.
  */
-struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE gesture_recognizer_new(struct RecognizerParams params,
-                                                                           struct SensorParams sensor_params);
+enum RecognizerStatus gesture_recognizer_init(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                              struct RecognizerParams params,
+                                              struct SensorParams sensor_params);
 
 /**
  * Resets the gesture recognizer with the given parameters.
  *
- * Clears the history, ongoing predictions and resets the internal state.
+ * Clears the history, ongoing predictions and resets the internal state,
+ * including the adaptive threshold, the zone filter and the presence detection.
+ * Returns `RecognizerStatusInitFailure` and keeps the current parameters if the mounting orientation is not supported.
  */
 enum RecognizerStatus gesture_recognizer_reset(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
                                                struct RecognizerParams params,
@@ -290,6 +1306,227 @@ enum RecognizerStatus gesture_recognizer_update(struct GestureRecognizer_RES_X__
                                                 struct SensorMeasurement_RES_X__RES_Y measurement,
                                                 struct RecognizerResult *gesture_result);
 
+/**
+ * Updates the gesture recognizer with a new measurement and the quality of its zones.
+ *
+ * Zones that are not accepted by the quality thresholds of the sensor parameters are ignored,
+ * the others are weighted with their confidence.
+ */
+enum RecognizerStatus gesture_recognizer_update_with_quality(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                             struct SensorMeasurement_RES_X__RES_Y measurement,
+                                                             const struct ZoneQuality (*zone_quality)[RES_Y][RES_X],
+                                                             struct RecognizerResult *gesture_result);
+
+/**
+ * Initializes a new gesture recognizer with a resolution that is selected at runtime, up to `RES_X` and `RES_Y`.
+ *
+ * Initially at the maximum resolution.
+ * Returns the same status as `gesture_recognizer_init`.
+ *
+ * # Safety
+ *
+ * `gesture_recognizer` must be `NULL` or point to memory valid for writing a gesture recognizer.
+ */
+enum RecognizerStatus runtime_res_gesture_recognizer_init(struct RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                          struct RecognizerParams params,
+                                                          struct SensorParams sensor_params);
+
+/**
+ * Resets the runtime resolution gesture recognizer with the given parameters.
+ */
+enum RecognizerStatus runtime_res_gesture_recognizer_reset(struct RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                           struct RecognizerParams params,
+                                                           struct SensorParams sensor_params,
+                                                           uint32_t now);
+
+/**
+ * Changes the resolution of the measurements, resetting the recognizer and clearing the background.
+ *
+ * Returns `RecognizerStatusInvalidInput` if the resolution is zero or exceeds `RES_X` or `RES_Y`.
+ */
+enum RecognizerStatus runtime_res_gesture_recognizer_set_resolution(struct RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                                    uintptr_t res_x,
+                                                                    uintptr_t res_y,
+                                                                    uint32_t now);
+
+/**
+ * Updates the runtime resolution gesture recognizer with a new measurement at the current resolution.
+ *
+ * `zone_dist` and the optional `zone_quality` (may be `NULL`) contain `n_zones` zones in row-major order.
+ * Returns `RecognizerStatusInvalidInput` if a required pointer is `NULL`
+ * or if `n_zones` does not match the current resolution.
+ *
+ * # Safety
+ *
+ * `gesture_recognizer` and `gesture_result` must be `NULL` or valid,
+ * `zone_dist` must point to `n_zones` values, `zone_quality` must be `NULL` or point to `n_zones` values.
+ */
+enum RecognizerStatus runtime_res_gesture_recognizer_update(struct RuntimeResGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                            const float *zone_dist,
+                                                            const struct ZoneQuality *zone_quality,
+                                                            uintptr_t n_zones,
+                                                            uint32_t time_ms,
+                                                            struct RecognizerResult *gesture_result);
+
+/**
+ * Updates the gesture recognizer with a new measurement with multiple targets per zone.
+ *
+ * The nearest valid target of every zone is used for recognition.
+ */
+enum RecognizerStatus gesture_recognizer_update_multi_target(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                             struct MultiTargetMeasurement_RES_X__RES_Y__TARGETS_PER_ZONE measurement,
+                                                             struct RecognizerResult *gesture_result);
+
+/**
+ * Whether something is currently present in presence mode.
+ */
+bool gesture_recognizer_is_present(const struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * Gets the current furthest hand distance for gesture recognition.
+ *
+ * Either the configured fixed distance or the current adaptive distance, depending on the threshold mode.
+ */
+float gesture_recognizer_gesture_threshold_dist(const struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * Gets the per-zone range calibration of the gesture recognizer.
+ */
+struct ZoneCalibration_RES_X__RES_Y gesture_recognizer_calibration(const struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * Sets the per-zone range calibration, that is applied to every measurement before the hand is recognized.
+ */
+void gesture_recognizer_set_calibration(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                        struct ZoneCalibration_RES_X__RES_Y calibration);
+
+/**
+ * A new zone calibrator without any frames.
+ */
+struct ZoneCalibrator_RES_X__RES_Y zone_calibrator_new(void);
+
+/**
+ * Adds a frame of a flat target, that is perpendicular to the sensor axis at the given distance.
+ */
+void zone_calibrator_add_frame(struct ZoneCalibrator_RES_X__RES_Y *calibrator,
+                               struct SensorMeasurement_RES_X__RES_Y measurement,
+                               float target_dist,
+                               struct SensorParams sensor_params);
+
+/**
+ * Computes the zone calibration from the added frames.
+ */
+struct ZoneCalibration_RES_X__RES_Y zone_calibrator_calibration(const struct ZoneCalibrator_RES_X__RES_Y *calibrator);
+
+/**
+ * Learns the static background from a measurement with no hand present.
+ *
+ * Should be called with several frames. Afterwards only zones that are significantly closer
+ * than the background count towards hand recognition.
+ */
+enum RecognizerStatus gesture_recognizer_calibrate_background(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                                              struct SensorMeasurement_RES_X__RES_Y measurement);
+
+/**
+ * Gets the calibrated background, e.g. to store it.
+ */
+struct BackgroundModel_RES_X__RES_Y gesture_recognizer_background(const struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * Sets the background, e.g. a previously stored one.
+ */
+void gesture_recognizer_set_background(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer,
+                                       struct BackgroundModel_RES_X__RES_Y background);
+
+/**
+ * Clears the background, all zones then count towards hand recognition again.
+ */
+void gesture_recognizer_clear_background(struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * Initializes a new fusion gesture recognizer with the given parameters and the sensor parameters of every sensor.
+ *
+ * Every sensor is placed in a shared frame through the pose of its sensor parameters.
+ * Returns `RecognizerStatusInitFailure` and leaves the gesture recognizer untouched if the presence mode is enabled
+ * or if the mounting orientation of a sensor is not supported, `RecognizerStatusInvalidInput` if `gesture_recognizer`
+ * is `NULL`.
+ *
+ * # Safety
+ *
+ * `gesture_recognizer` must be `NULL` or point to memory valid for writing a fusion gesture recognizer.
+ */
+enum RecognizerStatus fusion_gesture_recognizer_init(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                     struct RecognizerParams params,
+                                                     const struct SensorParams (*sensor_params)[FUSION_SENSORS]);
+
+/**
+ * Resets the fusion gesture recognizer with the given parameters.
+ *
+ * Clears the history, ongoing predictions and resets the internal state,
+ * including the adaptive thresholds and the zone filters.
+ *
+ * Returns `RecognizerStatusInitFailure` and keeps the current parameters if the presence mode is enabled
+ * or if the mounting orientation of a sensor is not supported.
+ */
+enum RecognizerStatus fusion_gesture_recognizer_reset(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                      struct RecognizerParams params,
+                                                      const struct SensorParams (*sensor_params)[FUSION_SENSORS],
+                                                      uint32_t now);
+
+/**
+ * Updates the fusion gesture recognizer with new measurements of every sensor.
+ *
+ * The time must be monotonically increasing (usually coming from a systick timer).
+ */
+enum RecognizerStatus fusion_gesture_recognizer_update(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                       const struct SensorMeasurement_RES_X__RES_Y (*measurements)[FUSION_SENSORS],
+                                                       struct RecognizerResult *gesture_result);
+
+/**
+ * Sets the per-zone range calibration of a sensor of the fusion gesture recognizer.
+ */
+enum RecognizerStatus fusion_gesture_recognizer_set_calibration(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                                uintptr_t sensor,
+                                                                struct ZoneCalibration_RES_X__RES_Y calibration);
+
+/**
+ * Learns the static background of a sensor of the fusion gesture recognizer from a measurement with no hand present.
+ */
+enum RecognizerStatus fusion_gesture_recognizer_calibrate_background(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                                     uintptr_t sensor,
+                                                                     struct SensorMeasurement_RES_X__RES_Y measurement);
+
+/**
+ * Clears the background of a sensor of the fusion gesture recognizer.
+ */
+enum RecognizerStatus fusion_gesture_recognizer_clear_background(struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer,
+                                                                 uintptr_t sensor);
+
+/**
+ * Gets the hand tracker of the fusion gesture recognizer, providing the estimated hand position and velocity.
+ */
+const struct HandTracker *fusion_gesture_recognizer_tracker(const struct FusionGestureRecognizer_RES_X__RES_Y__HISTORY_SIZE__FUSION_SENSORS *gesture_recognizer);
+
+/**
+ * Gets the hand tracker of the gesture recognizer, providing the estimated hand position and velocity.
+ */
+const struct HandTracker *gesture_recognizer_tracker(const struct GestureRecognizer_RES_X__RES_Y__HISTORY_SIZE *gesture_recognizer);
+
+/**
+ * The estimated hand position of the tracker (mm).
+ */
+struct CoordsCartesian hand_tracker_position(const struct HandTracker *tracker);
+
+/**
+ * The estimated hand velocity of the tracker (mm/s).
+ */
+struct CoordsCartesian hand_tracker_velocity(const struct HandTracker *tracker);
+
+/**
+ * Whether the hand is currently tracked.
+ */
+bool hand_tracker_is_tracking(const struct HandTracker *tracker);
+
 /**
  * Converts cartesian to spherical coordinates.
  */